@@ -1,8 +1,7 @@
-use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
 use quote::quote;
 
-use crate::schema::{FieldIdentifier, KeyStrategy, Schema, SchemaKey};
+use crate::schema::{ComputedIndex, FieldIdentifier, KeyStrategy, Schema, SchemaKey};
 
 pub struct Generator(Schema);
 
@@ -27,15 +26,16 @@ impl Generator {
 
         let trait_impls = self.generate_main_impl(&key_type, &index_values);
         key_impl.extend(trait_impls);
+        key_impl.extend(self.generate_schema_impl());
 
         TokenStream::from(key_impl)
     }
     fn generate_keys(&self) -> Vec<SchemaKey> {
         match &self.0.key_strategy {
-            KeyStrategy::Autoincrement => {
+            KeyStrategy::Autoincrement(ty) => {
                 vec![SchemaKey {
                     field_id: FieldIdentifier::Indexed(0),
-                    ty: syn::parse_quote!(u64),
+                    ty: ty.clone(),
                 }]
             }
             KeyStrategy::FieldKeys(keys) => keys.clone(),
@@ -66,12 +66,22 @@ impl Generator {
 
         let key_trait = self.generate_key_trait_impl(key_type, keys);
 
+        // Every proper leading subset of a composite key can be used for prefix scans
+        let prefixes = (1..field_types.len()).map(|len| {
+            let prefix_types = &field_types[..len];
+            quote! {
+                impl ::kivis::KeyPrefix<#key_type> for (#(#prefix_types,)*) {}
+            }
+        });
+
         quote! {
             #(#other_attrs)*
             #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
             #visibility struct #key_type(#(pub #field_types),*);
 
             #key_trait
+
+            #(#prefixes)*
         }
     }
 
@@ -84,13 +94,12 @@ impl Generator {
         let (impl_generics, ty_generics, where_clause) = self.0.generics.split_for_impl();
 
         match &self.0.key_strategy {
-            KeyStrategy::Autoincrement => {
-                // Generate Incrementable for autoincrement keys
+            KeyStrategy::Autoincrement(_) => {
+                // Generate Incrementable for autoincrement keys, delegating to the id type
                 quote! {
                     impl ::kivis::Incrementable for #key_type {
-                        // const BOUNDS: (Self, Self) = (#key_type(0), #key_type(u64::MAX));
                         fn next_id(&self) -> ::core::option::Option<Self> {
-                            self.0.checked_add(1).map(|id| #key_type(id))
+                            ::kivis::Incrementable::next_id(&self.0).map(#key_type)
                         }
                     }
                 }
@@ -136,39 +145,93 @@ impl Generator {
         let mut index_values = Vec::new();
 
         for (i, index) in self.0.indexes.iter().enumerate() {
-            // Generate a name for the index type based on field identifier
-            let index_type_suffix = match &index.field_id {
-                FieldIdentifier::Named(field_name) => field_name.to_string().to_case(Case::Pascal),
-                FieldIdentifier::Indexed(idx) => format!("Field{idx}"),
+            let index_name = syn::Ident::new(&format!("{name}{}Index", index.name), name.span());
+            let index_types: Vec<_> = match (&index.computed, &index.each, &index.sparse) {
+                (Some(computed), _, _) => vec![&computed.ty],
+                (None, Some(inner), _) | (None, None, Some(inner)) => vec![inner],
+                (None, None, None) => index.fields.iter().map(|f| &f.ty).collect(),
             };
-            let index_name =
-                syn::Ident::new(&format!("{name}{index_type_suffix}Index"), name.span());
-            let index_type = &index.ty;
+
+            // Every proper leading subset of a composite index can be used for prefix scans
+            let prefixes = (1..index_types.len()).map(|len| {
+                let prefix_types = &index_types[..len];
+                quote! {
+                    impl ::kivis::IndexPrefix<#index_name> for (#(#prefix_types,)*) {}
+                }
+            });
 
             let current_index_impl = quote! {
                 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
-                #visibility struct #index_name(pub #index_type);
+                #visibility struct #index_name(#(pub #index_types),*);
 
                 impl ::kivis::Index for #index_name {
                     type Key = #key_type;
                     type Record = #name;
                     const INDEX: u8 = #i as u8;
                 }
+
+                #(#prefixes)*
             };
             index_impl.extend(current_index_impl);
+            if index.unique {
+                index_impl.extend(quote! {
+                    impl ::kivis::UniqueIndex for #index_name {}
+                });
+            }
+            if !index.include.is_empty() {
+                let projection_name =
+                    syn::Ident::new(&format!("{name}{}Projection", index.name), name.span());
+                let (fields, types) = projection_fields(&index.include);
+                index_impl.extend(quote! {
+                    #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+                    #visibility struct #projection_name {
+                        #(pub #fields: #types,)*
+                    }
+
+                    impl ::kivis::CoveringIndex for #index_name {
+                        type Projection = #projection_name;
+                    }
+                });
+            }
+
+            let mut field_accesses = index
+                .fields
+                .iter()
+                .map(|field| field_access(&field.field_id));
 
-            // Generate field access based on field identifier type
-            let field_access = match &index.field_id {
-                FieldIdentifier::Named(field_name) => {
-                    quote! { &self.#field_name }
+            // Computed from the field, or from the whole record on struct-level indexes,
+            // the `element`-th item of a collection on multi-valued indexes, the value of an `Option` on sparse ones,
+            // otherwise the fields are serialized one after another, matching the layout of the index type
+            let index_value = if let Some(ComputedIndex { with, ty }) = &index.computed {
+                let argument = field_accesses.next().unwrap_or(quote! { self });
+                quote! {
+                    let value: #ty = #with(#argument);
+                    serializer.serialize(buffer, &value)?;
+                }
+            } else if let Some(element_type) = &index.each {
+                let collection = field_accesses.next();
+                quote! {
+                    if let ::core::option::Option::Some(value) =
+                        ::core::iter::IntoIterator::into_iter(#collection).nth(element)
+                    {
+                        let value: &#element_type = value;
+                        serializer.serialize(buffer, value)?;
+                    }
+                }
+            } else if let Some(value_type) = &index.sparse {
+                let option = field_accesses.next();
+                quote! {
+                    if let ::core::option::Option::Some(value) = #option {
+                        let value: &#value_type = value;
+                        serializer.serialize(buffer, value)?;
+                    }
                 }
-                FieldIdentifier::Indexed(idx) => {
-                    let index = syn::Index::from(*idx);
-                    quote! { &self.#index }
+            } else {
+                quote! {
+                    #(serializer.serialize(buffer, #field_accesses)?;)*
                 }
             };
-
-            index_values.push(field_access);
+            index_values.push(index_value);
         }
 
         (index_impl, index_values)
@@ -188,15 +251,65 @@ impl Generator {
             };
         };
         let indices = 0..index_count;
+        // Multi-valued indexes produce their first entry
+        let first_entries = self
+            .0
+            .indexes
+            .iter()
+            .zip(index_values)
+            .map(|(index, value)| {
+                if index.each.is_some() {
+                    quote! {
+                        let element = 0usize;
+                        #value
+                    }
+                } else {
+                    value.clone()
+                }
+            });
+        let unique_indexes = (0..index_count).filter(|&i| self.0.indexes[usize::from(i)].unique);
+
+        let entry_impls = self.generate_entry_impls(index_values);
+        let covering_impl = self.generate_covering_impl();
+        let covering_indexes =
+            (0..index_count).filter(|&i| !self.0.indexes[usize::from(i)].include.is_empty());
+
+        let derived_key = match &self.0.key_strategy {
+            KeyStrategy::Autoincrement(_) => quote! {},
+            KeyStrategy::FieldKeys(_) | KeyStrategy::Derived(_) => quote! {
+                fn derived_key(&self) -> ::core::option::Option<Self::Key> {
+                    ::core::option::Option::Some(<Self as ::kivis::DeriveKey>::key(self))
+                }
+            },
+        };
+
+        let fingerprint = self.generate_fingerprint();
+        let declared_scope = self.0.scope.map(|scope| {
+            quote! {
+                const DECLARED_SCOPE: ::core::option::Option<u8> = ::core::option::Option::Some(#scope);
+            }
+        });
+
+        let version = self.0.version.unwrap_or(1);
+        let version_items = self.generate_version_items();
 
         quote! {
             impl #impl_generics ::kivis::RecordKey for #key_type #ty_generics #where_clause {
                 type Record = #name;
             }
 
+            impl #impl_generics ::kivis::Versioned for #name #ty_generics #where_clause {
+                const VERSION: u32 = #version;
+            }
+
             impl #impl_generics ::kivis::DatabaseEntry for #name #ty_generics #where_clause {
                 type Key = #key_type;
                 const INDEX_COUNT_HINT: u8 = #index_count as u8;
+                const UNIQUE_INDEXES: &'static [u8] = &[#(#unique_indexes),*];
+                const COVERING_INDEXES: &'static [u8] = &[#(#covering_indexes),*];
+                const FINGERPRINT: &'static str = #fingerprint;
+                #declared_scope
+                #version_items
 
                 fn index_key<KU: ::kivis::Unifier>(
                     &self,
@@ -207,14 +320,430 @@ impl Generator {
                     match discriminator {
                         #(
                             #indices => {
-                                serializer.serialize(buffer, #index_values)?;
+                                #first_entries
                             }
                         )*
                         _ => {}
                     }
                     Ok(())
                 }
+
+                #entry_impls
+
+                #covering_impl
+
+                #derived_key
+            }
+        }
+    }
+
+    /// Generates the version of the record, whether its values are enveloped,
+    /// and the conversion of values stored at older versions.
+    fn generate_version_items(&self) -> proc_macro2::TokenStream {
+        let declared_version = self.0.version.map(|version| {
+            quote! {
+                const VERSION: u32 = #version;
+            }
+        });
+        let envelope = self.0.envelope.then(|| {
+            quote! {
+                const ENVELOPE: bool = true;
+            }
+        });
+        let upgrade = self.0.version.filter(|&version| version > 1).map(|_| {
+            quote! {
+                fn upgrade<VU: ::kivis::Unifier>(
+                    version: u32,
+                    data: &VU::D,
+                    unifier: &VU,
+                    enveloped: bool,
+                ) -> ::core::result::Result<::core::option::Option<Self>, VU::DeError> {
+                    ::kivis::read_version::<Self, VU>(unifier, version, data, enveloped)
+                }
+            }
+        });
+
+        quote! {
+            #declared_version
+            #envelope
+            #upgrade
+        }
+    }
+
+    /// Generates the `RecordSchema` implementation, describing the record at runtime.
+    fn generate_schema_impl(&self) -> proc_macro2::TokenStream {
+        let name = &self.0.name;
+        let (impl_generics, ty_generics, where_clause) = self.0.generics.split_for_impl();
+        let descriptors = |fields: &[SchemaKey]| {
+            let fields = fields.iter().map(|field| {
+                let field_name = match &field.field_id {
+                    FieldIdentifier::Named(ident) => ident.to_string(),
+                    FieldIdentifier::Indexed(index) => index.to_string(),
+                };
+                let type_name = type_name(&field.ty);
+                quote! {
+                    ::kivis::FieldDescriptor { name: #field_name, type_name: #type_name }
+                }
+            });
+            quote! { &[#(#fields),*] }
+        };
+
+        let record_name = name.to_string();
+        let fields = descriptors(&self.0.fields);
+        let key = match &self.0.key_strategy {
+            KeyStrategy::Autoincrement(ty) => {
+                let type_name = type_name(ty);
+                quote! { ::kivis::KeyDescriptor::Autoincrement { type_name: #type_name } }
+            }
+            KeyStrategy::FieldKeys(keys) => {
+                let keys = descriptors(keys);
+                quote! { ::kivis::KeyDescriptor::Fields(#keys) }
+            }
+            KeyStrategy::Derived(types) => {
+                let types = types.iter().map(type_name);
+                quote! { ::kivis::KeyDescriptor::Derived(&[#(#types),*]) }
+            }
+        };
+        let indexes = self.0.indexes.iter().map(|index| {
+            let index_name = &index.name;
+            let index_type = syn::Ident::new(&format!("{name}{index_name}Index"), name.span());
+            let fields = descriptors(&index.fields);
+            let unique = index.unique;
+            quote! {
+                ::kivis::IndexDescriptor {
+                    name: #index_name,
+                    discriminator: <#index_type as ::kivis::Index>::INDEX,
+                    fields: #fields,
+                    unique: #unique,
+                }
+            }
+        });
+
+        quote! {
+            impl #impl_generics ::kivis::RecordSchema for #name #ty_generics #where_clause {
+                const SCHEMA: ::kivis::RecordDescriptor = ::kivis::RecordDescriptor {
+                    name: #record_name,
+                    scope: <Self as ::kivis::Scope>::SCOPE,
+                    fields: #fields,
+                    key: #key,
+                    indexes: &[#(#indexes),*],
+                };
+            }
+        }
+    }
+
+    /// Describes the stored layout of the record, the types of its fields in order, its key, indexes and envelope,
+    /// one part per `; `. Names and the paths leading to types don't change the encoding, so they're left out.
+    ///
+    /// For example `fields(String, UserKey); key: autoincrement(u64); index 0: UserKey`.
+    fn generate_fingerprint(&self) -> String {
+        let types = |fields: &[SchemaKey]| {
+            fields
+                .iter()
+                .map(|field| layout_name(&field.ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut parts = vec![format!("fields({})", types(&self.0.fields))];
+        parts.push(match &self.0.key_strategy {
+            KeyStrategy::Autoincrement(ty) => format!("key: autoincrement({})", layout_name(ty)),
+            KeyStrategy::FieldKeys(keys) => format!("key: fields({})", types(keys)),
+            KeyStrategy::Derived(types) => {
+                let types = types.iter().map(layout_name).collect::<Vec<_>>();
+                format!("key: derived({})", types.join(", "))
+            }
+        });
+        for (discriminator, index) in self.0.indexes.iter().enumerate() {
+            let value = match (&index.computed, &index.each, &index.sparse) {
+                (Some(computed), _, _) => layout_name(&computed.ty),
+                (None, Some(element), _) => format!("each {}", layout_name(element)),
+                (None, None, Some(value)) => format!("sparse {}", layout_name(value)),
+                (None, None, None) => types(&index.fields),
+            };
+            let mut part = vec![format!("index {discriminator}: {value}")];
+            if index.unique {
+                part.push("unique".to_string());
             }
+            if !index.include.is_empty() {
+                part.push(format!("include({})", types(&index.include)));
+            }
+            parts.push(part.join(" "));
+        }
+        if self.0.envelope {
+            parts.push("envelope".to_string());
+        }
+        parts.join("; ")
+    }
+
+    /// Generates the entry count of indexes holding other than exactly one entry per record,
+    /// and the serialization of the entries of multi-valued indexes, if the record has any.
+    fn generate_entry_impls(
+        &self,
+        index_values: &[proc_macro2::TokenStream],
+    ) -> proc_macro2::TokenStream {
+        let mut counts = Vec::new();
+        let mut multi_valued = Vec::new();
+        for (i, (index, value)) in self.0.indexes.iter().zip(index_values).enumerate() {
+            let Ok(discriminator) = u8::try_from(i) else {
+                break;
+            };
+            let field = index.fields.first().map(|f| field_access(&f.field_id));
+            let count = if let Some(element_type) = &index.each {
+                // All entries are serialized in a single pass over the collection
+                let mut entries = quote! {
+                    for value in ::core::iter::IntoIterator::into_iter(#field) {
+                        let value: &#element_type = value;
+                        let mut entry = ::core::clone::Clone::clone(prefix);
+                        serializer.serialize(&mut entry, value)?;
+                        entries.extend(::core::option::Option::Some(entry));
+                    }
+                };
+                if let Some(when) = &index.when {
+                    entries = quote! {
+                        if #when(self) { #entries }
+                    };
+                }
+                multi_valued.push((discriminator, value, entries));
+                quote! {
+                    ::core::iter::Iterator::count(::core::iter::IntoIterator::into_iter(#field))
+                }
+            } else if index.sparse.is_some() {
+                quote! { usize::from(::core::option::Option::is_some(#field)) }
+            } else if index.when.is_some() {
+                quote! { 1 }
+            } else {
+                continue;
+            };
+            // Records not matching the predicate of a partial index have no entries
+            let count = match &index.when {
+                Some(when) => quote! {
+                    if #when(self) { #count } else { 0 }
+                },
+                None => count,
+            };
+            counts.push((discriminator, count));
+        }
+
+        let mut impls = proc_macro2::TokenStream::new();
+        if !counts.is_empty() {
+            let (discriminators, counts): (Vec<_>, Vec<_>) = counts.into_iter().unzip();
+            impls.extend(quote! {
+                fn index_entry_count(&self, discriminator: u8) -> usize {
+                    match discriminator {
+                        #(#discriminators => #counts,)*
+                        _ => 1,
+                    }
+                }
+            });
+        }
+        if !multi_valued.is_empty() {
+            let discriminators: Vec<_> = multi_valued.iter().map(|(d, _, _)| d).collect();
+            let values = multi_valued.iter().map(|(_, value, _)| value);
+            let entries = multi_valued.iter().map(|(_, _, entries)| entries);
+            impls.extend(quote! {
+                fn index_entry<KU: ::kivis::Unifier>(
+                    &self,
+                    buffer: &mut KU::D,
+                    discriminator: u8,
+                    element: usize,
+                    serializer: &KU,
+                ) -> core::result::Result<(), kivis::BufferOverflowOr<KU::SerError>> {
+                    match discriminator {
+                        #(
+                            #discriminators => {
+                                #values
+                            }
+                        )*
+                        _ => return self.index_key(buffer, discriminator, serializer),
+                    }
+                    Ok(())
+                }
+
+                fn index_entries<KU: ::kivis::Unifier>(
+                    &self,
+                    prefix: &KU::D,
+                    discriminator: u8,
+                    serializer: &KU,
+                    entries: &mut impl ::core::iter::Extend<KU::D>,
+                ) -> core::result::Result<(), kivis::BufferOverflowOr<KU::SerError>> {
+                    match discriminator {
+                        #(
+                            #discriminators => {
+                                #entries
+                            }
+                        )*
+                        _ => {
+                            for element in 0..self.index_entry_count(discriminator) {
+                                let mut entry = ::core::clone::Clone::clone(prefix);
+                                self.index_entry(&mut entry, discriminator, element, serializer)?;
+                                entries.extend(::core::option::Option::Some(entry));
+                            }
+                        }
+                    }
+                    Ok(())
+                }
+            });
+        }
+        impls
+    }
+
+    /// Generates the serialization of the projections stored by covering indexes, if the record has any,
+    /// and the deserialization of the primary keys stored before them.
+    ///
+    /// The projection is serialized from references to the fields, with the same layout as the generated projection type,
+    /// which is read back together with the key.
+    fn generate_covering_impl(&self) -> proc_macro2::TokenStream {
+        let name = &self.0.name;
+        let mut owners = Vec::new();
+        let covering: Vec<_> = self
+            .0
+            .indexes
+            .iter()
+            .enumerate()
+            .filter(|(_, index)| !index.include.is_empty())
+            .filter_map(|(i, index)| {
+                let discriminator = u8::try_from(i).ok()?;
+                let projection_name = format!("{name}{}Projection", index.name);
+                let projection_type = syn::Ident::new(&projection_name, name.span());
+                owners.push(quote! {
+                    #discriminator => unifier
+                        .deserialize::<(Self::Key, #projection_type)>(data)
+                        .map(|(key, _)| key),
+                });
+                let (fields, _) = projection_fields(&index.include);
+                let generics: Vec<_> = (0..fields.len())
+                    .map(|i| syn::Ident::new(&format!("T{i}"), name.span()))
+                    .collect();
+                Some(quote! {
+                    #discriminator => {
+                        #[derive(serde::Serialize)]
+                        #[serde(rename = #projection_name)]
+                        struct Projection<#(#generics),*> {
+                            #(#fields: #generics,)*
+                        }
+                        let projection = Projection {
+                            #(#fields: &self.#fields,)*
+                        };
+                        serializer.serialize(buffer, &(key, projection))?;
+                    }
+                })
+            })
+            .collect();
+        if covering.is_empty() {
+            return quote! {};
+        }
+
+        quote! {
+            fn index_value<VU: ::kivis::Unifier>(
+                &self,
+                buffer: &mut VU::D,
+                discriminator: u8,
+                key: &Self::Key,
+                serializer: &VU,
+            ) -> core::result::Result<(), kivis::BufferOverflowOr<VU::SerError>> {
+                match discriminator {
+                    #(#covering)*
+                    _ => {
+                        serializer.serialize(buffer, key)?;
+                    }
+                }
+                Ok(())
+            }
+
+            fn index_owner<VU: ::kivis::Unifier>(
+                data: &VU::D,
+                discriminator: u8,
+                unifier: &VU,
+            ) -> core::result::Result<Self::Key, VU::DeError> {
+                match discriminator {
+                    #(#owners)*
+                    _ => unifier.deserialize(data),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the names and types of the fields stored in a projection.
+fn projection_fields(include: &[SchemaKey]) -> (Vec<&syn::Ident>, Vec<&syn::Type>) {
+    include
+        .iter()
+        .filter_map(|field| match &field.field_id {
+            FieldIdentifier::Named(name) => Some((name, &field.ty)),
+            FieldIdentifier::Indexed(_) => None,
+        })
+        .unzip()
+}
+
+/// Accesses a field of the record by reference.
+fn field_access(field_id: &FieldIdentifier) -> proc_macro2::TokenStream {
+    match field_id {
+        FieldIdentifier::Named(field_name) => quote! { &self.#field_name },
+        FieldIdentifier::Indexed(idx) => {
+            let index = syn::Index::from(*idx);
+            quote! { &self.#index }
+        }
+    }
+}
+
+/// Renders a type as written in the source, without the spaces between tokens added by `quote`.
+fn type_name(ty: &syn::Type) -> String {
+    compact(&quote!(#ty).to_string())
+}
+
+/// Renders a type without the paths leading to it or to its type arguments,
+/// so that `std::string::String` and `String` are named alike.
+fn layout_name(ty: &syn::Type) -> String {
+    let mut ty = ty.clone();
+    strip_paths(&mut ty);
+    type_name(&ty)
+}
+
+fn strip_paths(ty: &mut syn::Type) {
+    match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => {
+            let path = &mut type_path.path;
+            path.leading_colon = None;
+            path.segments = path.segments.iter().last().cloned().into_iter().collect();
+            if let Some(segment) = path.segments.last_mut()
+                && let syn::PathArguments::AngleBracketed(arguments) = &mut segment.arguments
+            {
+                for argument in &mut arguments.args {
+                    if let syn::GenericArgument::Type(ty) = argument {
+                        strip_paths(ty);
+                    }
+                }
+            }
+        }
+        syn::Type::Tuple(tuple) => tuple.elems.iter_mut().for_each(strip_paths),
+        syn::Type::Array(array) => strip_paths(&mut array.elem),
+        syn::Type::Slice(slice) => strip_paths(&mut slice.elem),
+        syn::Type::Reference(reference) => strip_paths(&mut reference.elem),
+        syn::Type::Paren(paren) => strip_paths(&mut paren.elem),
+        syn::Type::Group(group) => strip_paths(&mut group.elem),
+        _ => {}
+    }
+}
+
+/// Drops spaces between tokens unless both sides are words, as in `&'a mut T`, keeping one after commas.
+fn compact(tokens: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut result = String::with_capacity(tokens.len());
+    let mut chars = tokens.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ' ' {
+            result.push(c);
+            continue;
+        }
+        let Some(&next) = chars.peek() else {
+            break;
+        };
+        if (result.ends_with(is_word) && is_word(next))
+            || (result.ends_with(',') && !matches!(next, ')' | ']' | '>'))
+        {
+            result.push(' ');
         }
     }
+    result
 }
@@ -19,18 +19,41 @@ use crate::schema::Schema;
 ///
 /// This macro generates the necessary traits and types for a struct to be used as a database record in Kivis.
 /// It creates key types, index types, and implements the required traits for database operations.
+/// It also implements `RecordSchema`, describing the record's fields, key and indexes at runtime.
+/// For keys made of several fields, `KeyPrefix` is implemented for tuples of the leading fields,
+/// allowing prefix scans with `Database::iter_keys_with_prefix`.
 ///
 /// # Attributes
 ///
 /// - `#[key]`: Marks fields as part of the primary key
 /// - `#[index]`: Marks fields for secondary indexing
+/// - `#[index(unique)]`: Marks fields for a unique secondary index, allowing each value to be held by one record only
+/// - `#[index(name = "by_status_date", fields(status, created_at))]`: On the struct, declares an index spanning several fields,
+///   generating `StructByStatusDateIndex(pub A, pub B)`. Without `name`, the type is named after the fields.
+///   Tuples of the leading fields implement `IndexPrefix`, allowing prefix scans with `Database::iter_by_index_with_prefix`
+/// - `#[index(with = path::to::fn, ty = Type)]`: Indexes the value computed by the function, called with a reference
+///   to the field, or to the whole record on the struct, where a `name` is required. The index type wraps `Type`.
+///   A field may carry several `#[index]` attributes, distinguished by `name`
+/// - `#[index(each)]`: Indexes every element of a collection field, such as `Vec<T>` or `[T; N]`, with one entry each.
+///   The index type wraps the element type, which can be given with `ty = Type` when it cannot be inferred
+/// - `#[index(sparse)]`: Indexes the value of an `Option` field, skipping records where it is `None`
+/// - `#[index(when = Self::predicate)]`: Only indexes records for which the predicate, called with the record, returns `true`
+/// - `#[index(include(a, b))]`: Makes the index covering, storing the listed fields in its entries as a generated
+///   `StructIndexNameProjection`, which `Database::iter_projection_by_index` returns without reading the records
 /// - `#[derived_key(Type1, Type2, ...)]`: Specifies types for a derived key (mutually exclusive with `#[key]`)
+/// - `#[record(scope = 7)]`: Sets the scope of the table, used by `manifest!` instead of the position of the type.
+///   Explicit scopes keep the data on disk in place when the manifest is reordered
+/// - `#[record(version = 2)]`: Sets the version of the record (defaults to 1). Records above version 1 must implement
+///   `Migrate`, converting from the previous definition, and `Database::migrate` rewrites values stored at older versions
+/// - `#[record(envelope)]`: Stores each value together with the version that wrote it, so that values of older versions
+///   are converted when they're read, without migrating the table first
+/// - `#[autoincrement(u32)]`: Sets the type of autoincrement ids, any type implementing `Incrementable` (defaults to `u64`)
 ///
 /// # Key Strategies
 ///
 /// The Record derive macro supports three key strategies:
 ///
-/// 1. **Autoincrement keys**: No `#[key]` fields or `#[derived_key]` attribute (default u64 autoincrement)
+/// 1. **Autoincrement keys**: No `#[key]` fields or `#[derived_key]` attribute (u64 ids, unless set with `#[autoincrement(...)]`)
 /// 2. **Field keys**: Fields marked with `#[key]` attribute (derived from struct fields)
 /// 3. **Derived keys**: Struct with `#[derived_key(...)]` attribute (requires manual `DeriveKey` implementation)
 ///
@@ -62,7 +85,7 @@ use crate::schema::Schema;
 /// ```
 ///
 /// For complete working examples, see the tests in the `tests/` directory.
-#[proc_macro_derive(Record, attributes(key, index, derived_key))]
+#[proc_macro_derive(Record, attributes(key, index, derived_key, autoincrement, record))]
 pub fn derive_record(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
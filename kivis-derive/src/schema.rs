@@ -1,3 +1,4 @@
+use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
 use syn::{Data, DeriveInput, Error, Fields, Ident, Type};
 
@@ -15,20 +16,140 @@ pub struct SchemaKey {
 
 #[derive(Clone)]
 pub enum KeyStrategy {
-    /// Autoincrement key (no explicit keys, no `#[derived_key(...)]` attribute)
-    Autoincrement,
+    /// Autoincrement key (no explicit keys, no `#[derived_key(...)]` attribute), of the type given
+    /// with `#[autoincrement(...)]` or `u64`
+    Autoincrement(Type),
     /// Explicit field keys (fields marked with #[key])
     FieldKeys(Vec<SchemaKey>),
     /// Derived keys (struct has `#[derived_key(...)]` attribute)
     Derived(Vec<Type>),
 }
 
+#[derive(Clone)]
+pub struct SchemaIndex {
+    /// Name of the index in Pascal case, the generated type is `{Struct}{name}Index`
+    pub name: String,
+    /// Indexed fields, serialized one after another (`fields(a, b)` on struct-level indexes)
+    pub fields: Vec<SchemaKey>,
+    /// Function computing the indexed value (`#[index(with = path, ty = Type)]`)
+    pub computed: Option<ComputedIndex>,
+    /// Element type of a multi-valued index over a collection field (`#[index(each)]`)
+    pub each: Option<Type>,
+    /// Value type of a sparse index over an `Option` field, skipping `None` (`#[index(sparse)]`)
+    pub sparse: Option<Type>,
+    /// Predicate on the record deciding whether it has entries in the index (`#[index(when = path)]`)
+    pub when: Option<syn::Path>,
+    /// Fields stored in a projection next to the primary key (`#[index(include(a, b))]`)
+    pub include: Vec<SchemaKey>,
+    /// Each index value belongs to at most one record (`#[index(unique)]`)
+    pub unique: bool,
+}
+
+/// An index over a value computed from a field, or from the whole record on struct-level indexes.
+#[derive(Clone)]
+pub struct ComputedIndex {
+    pub with: syn::Path,
+    pub ty: Type,
+}
+
+/// Options of an `#[index(...)]` attribute.
+#[derive(Default)]
+struct IndexOptions {
+    unique: bool,
+    each: bool,
+    sparse: bool,
+    when: Option<syn::Path>,
+    name: Option<syn::LitStr>,
+    fields: Vec<Ident>,
+    include: Vec<Ident>,
+    with: Option<syn::Path>,
+    ty: Option<Type>,
+}
+
+impl IndexOptions {
+    fn computed(&self, attr: &syn::Attribute) -> Result<Option<ComputedIndex>, Error> {
+        match (&self.with, &self.ty) {
+            (Some(with), Some(ty)) => Ok(Some(ComputedIndex {
+                with: with.clone(),
+                ty: ty.clone(),
+            })),
+            (None, None) => Ok(None),
+            // On multi-valued and sparse indexes, `ty` alone names the element type
+            (None, Some(_)) if self.each || self.sparse => Ok(None),
+            _ => Err(Error::new_spanned(
+                attr,
+                "computed indexes require both `with` and `ty`",
+            )),
+        }
+    }
+
+    /// Returns the type wrapped by a multi-valued or sparse index over a field of type `field_ty`,
+    /// the element of the collection or the value of the `Option`.
+    fn inner_type(&self, attr: &syn::Attribute, field_ty: &Type) -> Result<Option<Type>, Error> {
+        if !self.each && !self.sparse {
+            return Ok(None);
+        }
+        if self.each && self.sparse {
+            return Err(Error::new_spanned(
+                attr,
+                "`each` cannot be combined with `sparse`",
+            ));
+        }
+        if self.with.is_some() {
+            return Err(Error::new_spanned(
+                attr,
+                "`each` and `sparse` cannot be combined with `with`",
+            ));
+        }
+        self.ty
+            .clone()
+            .or_else(|| element_type(field_ty))
+            .map(Some)
+            .ok_or_else(|| {
+                Error::new_spanned(
+                    field_ty,
+                    "cannot infer the element type of the field, specify it with `ty = ...`",
+                )
+            })
+    }
+}
+
+/// Infers the element type of a collection or option: the first type argument of a path type such as
+/// `Vec<T>`, `BTreeSet<T>` or `Option<T>`, or the element of an array or slice.
+fn element_type(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Array(array) => Some((*array.elem).clone()),
+        Type::Slice(slice) => Some((*slice.elem).clone()),
+        Type::Reference(reference) => element_type(&reference.elem),
+        Type::Path(path) => {
+            let syn::PathArguments::AngleBracketed(arguments) =
+                &path.path.segments.last()?.arguments
+            else {
+                return None;
+            };
+            arguments.args.iter().find_map(|argument| match argument {
+                syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
 pub struct Schema {
     pub name: Ident,
     pub generics: syn::Generics,
     pub attrs: Vec<syn::Attribute>,
+    /// All fields of the struct, in declaration order
+    pub fields: Vec<SchemaKey>,
     pub key_strategy: KeyStrategy,
-    pub indexes: Vec<SchemaKey>,
+    pub indexes: Vec<SchemaIndex>,
+    /// Scope of the table, assigned by `manifest!` instead of the position (`#[record(scope = N)]`)
+    pub scope: Option<u8>,
+    /// Version of the record, converted from older ones with `Migrate` (`#[record(version = N)]`)
+    pub version: Option<u32>,
+    /// Whether values are stored with their version, to be converted when read (`#[record(envelope)]`)
+    pub envelope: bool,
 }
 
 impl Schema {
@@ -39,7 +160,12 @@ impl Schema {
         let attrs = input
             .attrs
             .iter()
-            .filter(|a| !a.path().is_ident("derived_key"))
+            .filter(|a| {
+                !a.path().is_ident("derived_key")
+                    && !a.path().is_ident("index")
+                    && !a.path().is_ident("autoincrement")
+                    && !a.path().is_ident("record")
+            })
             .cloned()
             .collect::<Vec<_>>();
 
@@ -79,6 +205,18 @@ impl Schema {
             .into());
         }
 
+        let fields = field_list
+            .iter()
+            .enumerate()
+            .map(|(index, field)| SchemaKey {
+                field_id: field
+                    .ident
+                    .clone()
+                    .map_or(FieldIdentifier::Indexed(index), FieldIdentifier::Named),
+                ty: field.ty.clone(),
+            })
+            .collect();
+
         // Find fields marked with #[key] attribute
         let mut key_fields = Vec::new();
         for (index, field) in field_list.iter().enumerate() {
@@ -111,9 +249,29 @@ impl Schema {
             })
             .unwrap_or_default();
 
+        // Look for the autoincrement attribute, choosing the type of the generated ids
+        let autoincrement = input
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("autoincrement"));
+        let autoincrement_type = autoincrement
+            .map(syn::Attribute::parse_args::<Type>)
+            .transpose()
+            .map_err(|e| compile_error(&e))?;
+        if let Some(attr) = autoincrement
+            && !(key_fields.is_empty() && derived_key_types.is_empty())
+        {
+            return Err(compile_error(&Error::new_spanned(
+                attr,
+                "#[autoincrement] only applies to records without #[key] fields or #[derived_key]",
+            )));
+        }
+
         // Determine key strategy and validate that only one is used
         let key_strategy = match (key_fields.is_empty(), derived_key_types.is_empty()) {
-            (true, true) => KeyStrategy::Autoincrement,
+            (true, true) => KeyStrategy::Autoincrement(
+                autoincrement_type.unwrap_or_else(|| syn::parse_quote!(u64)),
+            ),
             (false, true) => KeyStrategy::FieldKeys(key_fields),
             (true, false) => KeyStrategy::Derived(derived_key_types),
             (false, false) => {
@@ -126,32 +284,232 @@ impl Schema {
             }
         };
 
-        let index_fields = field_list
-            .iter()
-            .enumerate()
-            .filter_map(|(index, field)| {
-                if field.attrs.iter().any(|attr| attr.path().is_ident("index")) {
-                    let field_id = if let Some(ident) = &field.ident {
-                        FieldIdentifier::Named(ident.clone())
-                    } else {
-                        FieldIdentifier::Indexed(index)
-                    };
-                    Some(SchemaKey {
+        let mut scope = None;
+        let mut version = None;
+        let mut envelope = false;
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("record")) {
+            parse_record_options(attr, &mut scope, &mut version, &mut envelope)
+                .map_err(|e| compile_error(&e))?;
+        }
+
+        let mut index_fields = Vec::new();
+        for (index, field) in field_list.iter().enumerate() {
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("index")) {
+                let options = parse_index_options(attr).map_err(|e| compile_error(&e))?;
+                if !options.fields.is_empty() {
+                    return Err(compile_error(&Error::new_spanned(
+                        attr,
+                        "`fields` is only supported on struct-level #[index] attributes",
+                    )));
+                }
+                let computed = options.computed(attr).map_err(|e| compile_error(&e))?;
+                let inner = options
+                    .inner_type(attr, &field.ty)
+                    .map_err(|e| compile_error(&e))?;
+                let (each, sparse) = if options.each {
+                    (inner, None)
+                } else {
+                    (None, inner)
+                };
+                let include = resolve_fields(&options.include, &field_list)?;
+                let (field_id, field_name) = if let Some(ident) = &field.ident {
+                    let name = ident.to_string().to_case(Case::Pascal);
+                    (FieldIdentifier::Named(ident.clone()), name)
+                } else {
+                    (FieldIdentifier::Indexed(index), format!("Field{index}"))
+                };
+                index_fields.push(SchemaIndex {
+                    name: options
+                        .name
+                        .map_or(field_name, |name| name.value().to_case(Case::Pascal)),
+                    fields: vec![SchemaKey {
                         field_id,
                         ty: field.ty.clone(),
-                    })
-                } else {
-                    None
+                    }],
+                    computed,
+                    each,
+                    sparse,
+                    when: options.when,
+                    include,
+                    unique: options.unique,
+                });
+            }
+        }
+
+        // Struct-level indexes over several fields or the whole record come after the field-level ones
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("index")) {
+            let options = parse_index_options(attr).map_err(|e| compile_error(&e))?;
+            if options.each || options.sparse {
+                return Err(compile_error(&Error::new_spanned(
+                    attr,
+                    "`each` and `sparse` are only supported on field-level #[index] attributes",
+                )));
+            }
+            let computed = options.computed(attr).map_err(|e| compile_error(&e))?;
+            match (&computed, options.fields.is_empty(), &options.name) {
+                (None, true, _) => {
+                    return Err(compile_error(&Error::new_spanned(
+                        attr,
+                        "struct-level #[index] attributes require `fields(...)` or `with` and `ty`",
+                    )));
                 }
-            })
-            .collect::<Vec<_>>();
+                (Some(_), false, _) => {
+                    return Err(compile_error(&Error::new_spanned(
+                        attr,
+                        "struct-level computed indexes are called with the whole record and cannot list `fields`",
+                    )));
+                }
+                (Some(_), true, None) => {
+                    return Err(compile_error(&Error::new_spanned(
+                        attr,
+                        "struct-level computed indexes require a `name`",
+                    )));
+                }
+                _ => {}
+            }
+            let fields = resolve_fields(&options.fields, &field_list)?;
+            let include = resolve_fields(&options.include, &field_list)?;
+            let name = options.name.map_or_else(
+                || {
+                    options
+                        .fields
+                        .iter()
+                        .map(|ident| ident.to_string().to_case(Case::Pascal))
+                        .collect()
+                },
+                |name| name.value().to_case(Case::Pascal),
+            );
+            index_fields.push(SchemaIndex {
+                name,
+                fields,
+                computed,
+                each: None,
+                sparse: None,
+                when: options.when,
+                include,
+                unique: options.unique,
+            });
+        }
 
         Ok(Schema {
             name,
             generics,
             attrs,
+            fields,
             key_strategy,
             indexes: index_fields,
+            scope,
+            version,
+            envelope,
         })
     }
 }
+
+/// Looks up named fields of the struct listed in an `#[index]` attribute.
+fn resolve_fields(
+    idents: &[Ident],
+    field_list: &[&syn::Field],
+) -> Result<Vec<SchemaKey>, TokenStream> {
+    idents
+        .iter()
+        .map(|ident| {
+            field_list
+                .iter()
+                .find(|field| field.ident.as_ref() == Some(ident))
+                .map(|field| SchemaKey {
+                    field_id: FieldIdentifier::Named(ident.clone()),
+                    ty: field.ty.clone(),
+                })
+                .ok_or_else(|| compile_error(&Error::new_spanned(ident, "unknown field")))
+        })
+        .collect()
+}
+
+fn compile_error(error: &Error) -> TokenStream {
+    error.to_compile_error().into()
+}
+
+/// Parses the options of a `#[record]` attribute, such as `#[record(scope = 7, version = 2, envelope)]`.
+fn parse_record_options(
+    attr: &syn::Attribute,
+    scope: &mut Option<u8>,
+    version: &mut Option<u32>,
+    envelope: &mut bool,
+) -> Result<(), Error> {
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("scope") {
+            let value: syn::LitInt = meta.value()?.parse()?;
+            *scope = Some(value.base10_parse()?);
+            Ok(())
+        } else if meta.path.is_ident("version") {
+            let value: syn::LitInt = meta.value()?.parse()?;
+            let parsed = value.base10_parse()?;
+            if parsed == 0 {
+                return Err(Error::new_spanned(value, "versions start at 1"));
+            }
+            *version = Some(parsed);
+            Ok(())
+        } else if meta.path.is_ident("envelope") {
+            *envelope = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported record option, expected `scope`, `version` or `envelope`"))
+        }
+    })
+}
+
+/// Parses the options of an `#[index]` attribute, such as `#[index(unique)]`
+/// `#[index(name = "by_status_date", fields(status, created_at))]` or `#[index(with = normalize, ty = String)]`.
+fn parse_index_options(attr: &syn::Attribute) -> Result<IndexOptions, Error> {
+    let mut options = IndexOptions::default();
+    if let syn::Meta::List(_) = attr.meta {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("unique") {
+                options.unique = true;
+                Ok(())
+            } else if meta.path.is_ident("each") {
+                options.each = true;
+                Ok(())
+            } else if meta.path.is_ident("sparse") {
+                options.sparse = true;
+                Ok(())
+            } else if meta.path.is_ident("when") {
+                options.when = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                options.name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("fields") {
+                parse_field_list(&meta, &mut options.fields)
+            } else if meta.path.is_ident("include") {
+                parse_field_list(&meta, &mut options.include)
+            } else if meta.path.is_ident("with") {
+                options.with = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("ty") {
+                options.ty = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unsupported index option, expected `unique`, `each`, `sparse`, `when`, `name`, `fields`, `include`, `with` or `ty`",
+                ))
+            }
+        })?;
+    }
+    Ok(options)
+}
+
+/// Parses a parenthesized list of field names, such as `fields(a, b)`.
+fn parse_field_list(
+    meta: &syn::meta::ParseNestedMeta,
+    fields: &mut Vec<Ident>,
+) -> Result<(), Error> {
+    meta.parse_nested_meta(|field| {
+        let ident = field
+            .path
+            .get_ident()
+            .ok_or_else(|| field.error("expected a field name"))?;
+        fields.push(ident.clone());
+        Ok(())
+    })
+}
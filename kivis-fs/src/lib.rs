@@ -73,21 +73,26 @@
 //! - File contents use CSV format for the serialized data
 //! - The storage directory is created automatically if it doesn't exist
 //!
+//! The default [`CsvSerializer`] writes the parts of a key one after another, so scanned keys can't be
+//! read back and only lookups by key or index are available. Stores opened with
+//! [`FileStore::with_serializer`] and [`SeparatedCsvSerializer`] separate the parts with commas, which
+//! also supports iterating over keys and records, but changes every filename. Directories written with
+//! one serializer have to be opened with the same one.
+//!
 //! This makes the storage directory easy to navigate, backup, and inspect manually.
 
 mod error;
 mod repository;
 mod serializer;
 
-use kivis::Storage;
-
-use crate::serializer::CsvSerializer;
+use kivis::{Storage, Unifier};
 
 pub use crate::repository::FileStore;
+pub use crate::serializer::{CsvSerializer, SeparatedCsvSerializer};
 
-impl Storage for FileStore {
+impl<U: Unifier<D = String> + Default> Storage for FileStore<U> {
     type Repo = Self;
-    type Unifiers = (CsvSerializer, CsvSerializer);
+    type Unifiers = (U, U);
     fn repository(&self) -> &Self::Repo {
         self
     }
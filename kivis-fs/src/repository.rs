@@ -1,19 +1,22 @@
-use kivis::Repository;
-use std::{fs, path::PathBuf};
+use kivis::{Direction, Repository};
+use std::{fs, marker::PhantomData, path::PathBuf};
 
 use crate::error::FileStoreError;
+use crate::serializer::CsvSerializer;
 
 /// A file-based storage implementation that stores each key-value pair as a separate file.
 ///
 /// Uses CSV serialization with URL-encoded filenames for human-readable storage.
 /// Each record is stored in a `.dat` file within the configured directory.
+/// Keys and values are serialized with `U`, [`CsvSerializer`] unless opened with [`FileStore::with_serializer`].
 #[derive(Debug)]
-pub struct FileStore {
+pub struct FileStore<U = CsvSerializer> {
     /// The directory where all data files are stored.
     data_dir: PathBuf,
+    serializer: PhantomData<U>,
 }
 
-impl Repository for FileStore {
+impl<U> Repository for FileStore<U> {
     type K = String;
     type V = String;
     type Error = FileStoreError;
@@ -45,25 +48,37 @@ impl Repository for FileStore {
         }
     }
 
+    fn contains_entry(&self, key: &str) -> Result<bool, Self::Error> {
+        Ok(self.key_to_filename(key).try_exists()?)
+    }
+
+    fn count_range(&self, range: std::ops::Range<Self::K>) -> Result<usize, Self::Error> {
+        Ok(self.keys_in_range(&range)?.len())
+    }
+
     fn scan_range(
         &self,
         range: std::ops::Range<Self::K>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = Result<Self::K, Self::Error>>, Self::Error> {
-        let entries = fs::read_dir(&self.data_dir)?;
+        let keys = self.keys_in_range(&range)?;
+        Ok(direction.apply(keys.into_iter()).map(Ok))
+    }
 
-        let mut keys: Vec<String> = Vec::new();
-        for entry in entries.flatten() {
-            if let Some(filename) = entry.file_name().to_str()
-                && let Some(key) = self.filename_to_key(filename)
-                && key >= range.start
-                && key < range.end
-            {
-                keys.push(key);
+    fn scan_range_entries(
+        &self,
+        range: std::ops::Range<Self::K>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = Result<(Self::K, Self::V), Self::Error>>, Self::Error> {
+        let keys = self.keys_in_range(&range)?;
+        Ok(direction.apply(keys.into_iter()).filter_map(|key| {
+            match fs::read_to_string(self.key_to_filename(&key)) {
+                Ok(data) => Some(Ok((key, data))),
+                // The file was removed after the directory was listed.
+                Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => Some(Err(e.into())),
             }
-        }
-
-        keys.sort();
-        Ok(keys.into_iter().rev().map(Ok))
+        }))
     }
 }
 
@@ -76,9 +91,47 @@ impl FileStore {
     ///
     /// Returns an error if the directory cannot be created.
     pub fn new(data_dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        Self::with_serializer(data_dir)
+    }
+}
+
+impl<U> FileStore<U> {
+    /// Creates a new FileStore instance at the specified directory, serializing entries with `U`,
+    /// such as [`SeparatedCsvSerializer`](crate::SeparatedCsvSerializer).
+    ///
+    /// Creates the directory if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created.
+    pub fn with_serializer(data_dir: impl Into<PathBuf>) -> std::io::Result<Self> {
         let data_dir = data_dir.into();
         fs::create_dir_all(&data_dir)?;
-        Ok(Self { data_dir })
+        Ok(Self {
+            data_dir,
+            serializer: PhantomData,
+        })
+    }
+
+    /// Lists the keys of all data files within the range, sorted in ascending order.
+    fn keys_in_range(
+        &self,
+        range: &std::ops::Range<String>,
+    ) -> Result<Vec<String>, FileStoreError> {
+        let entries = fs::read_dir(&self.data_dir)?;
+
+        let mut keys: Vec<String> = Vec::new();
+        for entry in entries.flatten() {
+            if let Some(filename) = entry.file_name().to_str()
+                && let Some(key) = self.filename_to_key(filename)
+                && range.contains(&key)
+            {
+                keys.push(key);
+            }
+        }
+
+        keys.sort();
+        Ok(keys)
     }
 
     /// Converts a key string to a filesystem path.
@@ -60,23 +60,23 @@ impl CsvSerializer {
     }
 }
 
-impl Unifier for CsvSerializer {
-    type D = String;
-    type SerError = csv::Error;
-    type DeError = csv::Error;
-
-    fn serialize(
-        &self,
-        buffer: &mut Self::D,
+impl CsvSerializer {
+    /// Appends `data` to `buffer` as CSV, preceded by a comma if `separate` is set and the buffer isn't empty.
+    fn write(
+        buffer: &mut String,
         data: &impl Serialize,
-    ) -> Result<(usize, usize), BufferOverflowOr<Self::SerError>> {
+        separate: bool,
+    ) -> Result<(usize, usize), BufferOverflowOr<csv::Error>> {
+        if separate && !buffer.is_empty() {
+            buffer.push(',');
+        }
         let start = buffer.len();
         let mut writer = csv::WriterBuilder::new()
             .has_headers(false)
             .quote_style(csv::QuoteStyle::Necessary)
             .from_writer(Vec::new());
         writer.serialize(data)?;
-        writer.flush().map_err(Self::SerError::from)?;
+        writer.flush().map_err(csv::Error::from)?;
         let bytes = writer
             .into_inner()
             .map_err(|e| csv::Error::from(std::io::Error::other(e.to_string())))?;
@@ -88,7 +88,8 @@ impl Unifier for CsvSerializer {
         Ok((start, buffer.len()))
     }
 
-    fn deserialize<T: DeserializeOwned>(&self, data: &Self::D) -> Result<T, Self::DeError> {
+    /// Reads the first CSV record of `data`.
+    fn read<T: DeserializeOwned>(data: &str) -> Result<T, csv::Error> {
         let decoded = Self::decode_from_filename(data).ok_or_else(|| {
             csv::Error::from(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
@@ -109,6 +110,51 @@ impl Unifier for CsvSerializer {
     }
 }
 
+impl Unifier for CsvSerializer {
+    type D = String;
+    type SerError = csv::Error;
+    type DeError = csv::Error;
+
+    fn serialize(
+        &self,
+        buffer: &mut Self::D,
+        data: &impl Serialize,
+    ) -> Result<(usize, usize), BufferOverflowOr<Self::SerError>> {
+        Self::write(buffer, data, false)
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, data: &Self::D) -> Result<T, Self::DeError> {
+        Self::read(data)
+    }
+}
+
+/// A [`CsvSerializer`] that separates consecutive values written to the same buffer with a comma.
+///
+/// The prelude and the key of a stored entry then form a single CSV record, so scanned keys can be
+/// read back, as by [`Database::iter_all_records`](kivis::Database::iter_all_records).
+/// The filenames differ from those written by [`CsvSerializer`], so directories written by one can't be
+/// opened with the other.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SeparatedCsvSerializer;
+
+impl Unifier for SeparatedCsvSerializer {
+    type D = String;
+    type SerError = csv::Error;
+    type DeError = csv::Error;
+
+    fn serialize(
+        &self,
+        buffer: &mut Self::D,
+        data: &impl Serialize,
+    ) -> Result<(usize, usize), BufferOverflowOr<Self::SerError>> {
+        CsvSerializer::write(buffer, data, true)
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, data: &Self::D) -> Result<T, Self::DeError> {
+        CsvSerializer::read(data)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,6 +223,27 @@ mod tests {
         assert_eq!(CsvSerializer::decode_from_filename("test%GG"), None);
     }
 
+    #[test]
+    fn test_consecutive_values_concatenated() -> Result<(), Box<dyn std::error::Error>> {
+        let mut buffer = String::new();
+        CsvSerializer.serialize(&mut buffer, &(1u8, 0u8))?;
+        CsvSerializer.serialize(&mut buffer, &42u64)?;
+        assert_eq!(buffer, "1,042");
+        Ok(())
+    }
+
+    #[test]
+    fn test_consecutive_values_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+        let mut buffer = String::new();
+        SeparatedCsvSerializer.serialize(&mut buffer, &(1u8, 0u8))?;
+        SeparatedCsvSerializer.serialize(&mut buffer, &42u64)?;
+        assert_eq!(buffer, "1,0,42");
+
+        let decoded: (u8, u8, u64) = SeparatedCsvSerializer.deserialize(&buffer)?;
+        assert_eq!(decoded, (1, 0, 42));
+        Ok(())
+    }
+
     #[test]
     fn test_safe_chars() {
         let safe = "test_file-123.csv,data";
@@ -0,0 +1,100 @@
+use kivis::{Database, Direction, Migrate, Record, Versioned, manifest};
+use kivis_fs::{FileStore, SeparatedCsvSerializer};
+use serde::{Deserialize, Serialize};
+use tempfile::tempdir;
+
+mod before {
+    use super::*;
+
+    /// A visitor record stored with its version
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[record(envelope)]
+    pub struct Visitor {
+        pub name: String,
+    }
+
+    manifest![Gate: Visitor];
+}
+
+mod after {
+    use super::*;
+
+    /// The first version of the visitor record
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct VisitorV1 {
+        pub name: String,
+    }
+
+    impl Versioned for VisitorV1 {
+        const VERSION: u32 = 1;
+    }
+
+    impl Migrate for VisitorV1 {
+        type From = Self;
+
+        fn migrate(previous: Self) -> Self {
+            previous
+        }
+    }
+
+    /// A visitor record with the number of visits
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[record(version = 2, envelope)]
+    pub struct Visitor {
+        pub name: String,
+        pub visits: u32,
+    }
+
+    impl Migrate for Visitor {
+        type From = VisitorV1;
+
+        fn migrate(previous: VisitorV1) -> Self {
+            Self {
+                name: previous.name,
+                visits: 1,
+            }
+        }
+    }
+
+    manifest![Gate: Visitor];
+}
+
+#[test]
+fn test_envelope_upgrade() -> anyhow::Result<()> {
+    let temp_dir = tempdir()?;
+
+    let mut store: Database<_, before::Gate> = Database::new(
+        FileStore::<SeparatedCsvSerializer>::with_serializer(temp_dir.path())?,
+    )?;
+    let key = store.put(before::Visitor {
+        name: "Alice, Jr.".into(),
+    })?;
+    drop(store);
+
+    // Values written by the first version are converted when read
+    let mut store: Database<_, after::Gate> = Database::new(
+        FileStore::<SeparatedCsvSerializer>::with_serializer(temp_dir.path())?,
+    )?;
+    let key = after::VisitorKey(key.0);
+    store.put(after::Visitor {
+        name: "Bob".into(),
+        visits: 3,
+    })?;
+    let visitor = store.get(&key)?;
+    assert_eq!(
+        visitor,
+        Some(after::Visitor {
+            name: "Alice, Jr.".into(),
+            visits: 1,
+        })
+    );
+
+    // And can be written back in the new version
+    store.get_upgraded::<after::Visitor>(&key)?;
+    let visits = store
+        .iter_all_records::<after::Visitor>(Direction::Ascending)?
+        .map(|entry| entry.map(|(_, visitor)| visitor.visits))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(visits, vec![1, 3]);
+    Ok(())
+}
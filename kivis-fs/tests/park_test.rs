@@ -1,5 +1,5 @@
-use kivis::{Database, Lexicographic, Record, manifest};
-use kivis_fs::FileStore;
+use kivis::{Database, Direction, Lexicographic, Record, manifest};
+use kivis_fs::{FileStore, SeparatedCsvSerializer};
 use tempfile::tempdir;
 
 /// A user record with an indexed name field
@@ -71,9 +71,9 @@ fn test_flow() -> anyhow::Result<()> {
     assert_eq!(retrieved_fluffy.name, "Fluffy");
     assert_eq!(retrieved_fluffy.owner, alice_key.clone());
 
-    // Query by indexed fields
+    // Query by indexed fields, CSV keys don't sort like the values so only exact lookups are available
     let users_named_alice = store
-        .iter_by_index(UserNameIndex("Alice".into())..UserNameIndex("Bob".into()))?
+        .iter_by_index_exact(&UserNameIndex("Alice".into()))?
         .collect::<Result<Vec<_>, _>>()?;
     assert_eq!(users_named_alice, vec![alice_key.clone()]);
 
@@ -85,3 +85,41 @@ fn test_flow() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_listing_records() -> anyhow::Result<()> {
+    let temp_dir = tempdir()?;
+
+    // Keys can only be read back from stores separating their parts
+    let file_store = FileStore::<SeparatedCsvSerializer>::with_serializer(temp_dir.path())?;
+    let mut store: Database<_, Park> = Database::new(file_store)?;
+
+    let alice_key = store.put(User {
+        name: "Alice".into(),
+        email: "alice@example.com".into(),
+    })?;
+    let fluffy_key = store.put(Pet {
+        name: "Fluffy".into(),
+        owner: alice_key.clone(),
+        cat: true,
+    })?;
+    let rover_key = store.put(Pet {
+        name: "Rover".into(),
+        owner: alice_key,
+        cat: false,
+    })?;
+
+    // Records can be listed together with their keys
+    let pets = store
+        .iter_all_records::<Pet>(Direction::Descending)?
+        .map(|entry| entry.map(|(key, pet)| (key, pet.name)))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        pets,
+        vec![
+            (rover_key, "Rover".to_string()),
+            (fluffy_key, "Fluffy".to_string())
+        ]
+    );
+    Ok(())
+}
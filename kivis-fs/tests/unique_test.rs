@@ -0,0 +1,45 @@
+use kivis::{Database, DatabaseError, Record, manifest};
+use kivis_fs::FileStore;
+use tempfile::tempdir;
+
+/// A badge record whose holder is stored in its covering unique index
+#[derive(Record, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Badge {
+    #[key]
+    holder: String,
+    #[index(unique, include(level))]
+    serial: u32,
+    level: u8,
+}
+
+manifest![Office: Badge];
+
+#[test]
+fn test_unique_owner_sharing_a_prefix() -> anyhow::Result<()> {
+    let temp_dir = tempdir()?;
+    let mut store: Database<_, Office> = Database::new(FileStore::new(temp_dir.path())?)?;
+
+    store.insert(Badge {
+        holder: "abc".into(),
+        serial: 7,
+        level: 1,
+    })?;
+
+    // The stored entry of "abc" starts with "ab", but is held by another record
+    let result = store.insert(Badge {
+        holder: "ab".into(),
+        serial: 7,
+        level: 2,
+    });
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+
+    // The owner can still rewrite its own entry
+    store.insert(Badge {
+        holder: "abc".into(),
+        serial: 7,
+        level: 3,
+    })?;
+    let owner = store.get_by_unique(&BadgeSerialIndex(7))?;
+    assert_eq!(owner.map(|badge| badge.level), Some(3));
+    Ok(())
+}
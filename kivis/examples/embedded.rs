@@ -5,8 +5,8 @@ use ekv::flash::{Flash, PageID};
 use embassy_sync::blocking_mutex::raw::NoopRawMutex;
 use heapless::Vec;
 use kivis::{
-    ApplyError, BufferOverflowError, BufferOverflowOr, Record, Repository, Storage, Unified,
-    Unifier, manifest,
+    ApplyError, BufferOverflowError, BufferOverflowOr, Direction, OrderedUnifier, Record,
+    Repository, Storage, Unified, Unifier, manifest,
 };
 use ouroboros::self_referencing;
 use serde::Serialize;
@@ -183,6 +183,9 @@ impl From<BufferOverflowError> for EkvError {
 }
 
 /// Storage implementation using ekv with postcard serialization
+/// Maximum number of keys buffered by a descending scan.
+const SCAN_BUFFER_SIZE: usize = 32;
+
 pub struct EkvStorage<const SIZE: usize, const KEY_SIZE: usize, const VALUE_SIZE: usize> {
     db: ekv::Database<MockFlash<SIZE>, NoopRawMutex>,
 }
@@ -259,6 +262,7 @@ impl<const SIZE: usize, const KEY_SIZE: usize, const VALUE_SIZE: usize> Reposito
     fn scan_range(
         &self,
         range: Range<Self::K>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = Result<Self::K, Self::Error>>, Self::Error> {
         let iter = CursorIterBuilder {
             db: &self.db,
@@ -276,7 +280,22 @@ impl<const SIZE: usize, const KEY_SIZE: usize, const VALUE_SIZE: usize> Reposito
         }
         .build();
 
-        Ok(iter)
+        // ekv cursors only walk forward, so descending scans are buffered and reversed.
+        let mut buffered = Vec::<_, SCAN_BUFFER_SIZE>::new();
+        let ascending = match direction {
+            Direction::Ascending => Some(iter),
+            Direction::Descending => {
+                for key in iter {
+                    buffered
+                        .push(key)
+                        .map_err(|_| EkvError::BufferOverflow(BufferOverflowError))?;
+                }
+                buffered.reverse();
+                None
+            }
+        };
+
+        Ok(ascending.into_iter().flatten().chain(buffered))
     }
 
     fn apply<U, E>(
@@ -365,7 +384,11 @@ impl<const SIZE: usize, const KEY_SIZE: usize, const VALUE_SIZE: usize> Storage
     for EkvStorage<SIZE, KEY_SIZE, VALUE_SIZE>
 {
     type Repo = Self;
-    type Unifiers = (PostcardUnifier<KEY_SIZE>, PostcardUnifier<VALUE_SIZE>);
+    // Keys use the order-preserving encoding, so that range scans over them are supported.
+    type Unifiers = (
+        OrderedUnifier<Vec<u8, KEY_SIZE>>,
+        PostcardUnifier<VALUE_SIZE>,
+    );
     fn repository(&self) -> &Self::Repo {
         self
     }
@@ -426,7 +449,10 @@ fn main() {
 
     // 3. Iterate all (should see 4 values)
     let all_keys: Vec<_, 10> = db
-        .iter_keys(SensorReadingKey(0)..SensorReadingKey(255))
+        .iter_keys(
+            SensorReadingKey(0)..SensorReadingKey(255),
+            Direction::Ascending,
+        )
         .unwrap()
         .collect::<Result<Vec<_, 10>, _>>()
         .unwrap();
@@ -440,7 +466,10 @@ fn main() {
     // 4. Iterate over a range where only the 2 inner values should be shown
     // Keys are 1, 2, 3, 4, so range [2..4) should give us keys 2 and 3
     let range_keys: Vec<_, 10> = db
-        .iter_keys(SensorReadingKey(2)..SensorReadingKey(4))
+        .iter_keys(
+            SensorReadingKey(2)..SensorReadingKey(4),
+            Direction::Ascending,
+        )
         .unwrap()
         .collect::<Result<Vec<_, 10>, _>>()
         .unwrap();
@@ -454,7 +483,10 @@ fn main() {
     db.remove(&key4).unwrap();
 
     let remaining_keys: Vec<_, 10> = db
-        .iter_keys(SensorReadingKey(0)..SensorReadingKey(255))
+        .iter_keys(
+            SensorReadingKey(0)..SensorReadingKey(255),
+            Direction::Ascending,
+        )
         .unwrap()
         .collect::<Result<Vec<_, 10>, _>>()
         .unwrap();
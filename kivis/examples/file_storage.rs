@@ -2,7 +2,9 @@ use bincode::{
     config::Configuration,
     error::{DecodeError, EncodeError},
 };
-use kivis::{Database, DatabaseError, Record, Repository, Storage, manifest};
+use kivis::{
+    Database, DatabaseError, Direction, OrderedUnifier, Record, Repository, Storage, manifest,
+};
 use std::fs;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -62,7 +64,7 @@ impl FileStore {
 
 impl Storage for FileStore {
     type Repo = Self;
-    type Unifiers = (Configuration, Configuration);
+    type Unifiers = (OrderedUnifier, Configuration);
     fn repository(&self) -> &Self::Repo {
         self
     }
@@ -106,6 +108,7 @@ impl Repository for FileStore {
     fn scan_range(
         &self,
         range: std::ops::Range<Vec<u8>>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = Result<Vec<u8>, Self::Error>>, Self::Error> {
         let entries = fs::read_dir(&self.data_dir)?;
 
@@ -122,8 +125,7 @@ impl Repository for FileStore {
         }
 
         keys.sort();
-        keys.reverse(); // Match the Reverse order used in MemoryStorage
-        Ok(keys.into_iter().map(Ok))
+        Ok(direction.apply(keys.into_iter()).map(Ok))
     }
 }
 
@@ -176,7 +178,10 @@ fn main() -> Result<(), DatabaseError<FileStore>> {
 
     // Query by indexed fields
     let users_named_alice = store
-        .iter_by_index(UserNameIndex("Alice".into())..UserNameIndex("Alicf".into()))?
+        .iter_by_index(
+            UserNameIndex("Alice".into())..UserNameIndex("Alicf".into()),
+            Direction::Ascending,
+        )?
         .collect::<Result<Vec<_>, _>>()?;
     assert_eq!(users_named_alice, vec![alice_key.clone()]);
 
@@ -2,7 +2,7 @@
 // This demonstrates how to implement the Storage trait using HTTP requests
 
 use bincode::config::Configuration;
-use kivis::{BufferOverflowError, Repository, Storage};
+use kivis::{BufferOverflowError, Direction, OrderedUnifier, Repository, Storage};
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 use thiserror::Error;
@@ -64,7 +64,7 @@ impl Client {
 
 impl Storage for Client {
     type Repo = Self;
-    type Unifiers = (Configuration, Configuration);
+    type Unifiers = (OrderedUnifier, Configuration);
     fn repository(&self) -> &Self::Repo {
         self
     }
@@ -157,14 +157,22 @@ impl Repository for Client {
     fn scan_range(
         &self,
         range: Range<Self::K>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = Result<Self::K, Self::Error>>, Self::Error> {
         // Use hex encoding for binary data to avoid URL encoding issues
         let start = hex::encode(&range.start);
         let end = hex::encode(&range.end);
+        let order = match direction {
+            Direction::Ascending => "asc",
+            Direction::Descending => "desc",
+        };
 
         let response = self
             .client
-            .get(format!("{}/keys/{}/{}", self.base_url, start, end))
+            .get(format!(
+                "{}/keys/{}/{}/{}",
+                self.base_url, start, end, order
+            ))
             .send()
             .map_err(|e| ClientError::Http(e.to_string()))?;
 
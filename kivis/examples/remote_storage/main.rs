@@ -15,7 +15,7 @@ mod schema;
 mod server;
 
 use client::Client;
-use kivis::{Database, DatabaseError, MemoryStorage};
+use kivis::{Database, DatabaseError, Direction, MemoryStorage};
 use schema::*;
 use std::thread;
 use std::time::Duration;
@@ -91,6 +91,7 @@ fn main() -> Result<(), DatabaseError<Client>> {
     let alice_by_email = db
         .iter_by_index(
             UserEmailIndex("alice@example.com".into())..UserEmailIndex("alice@example.con".into()),
+            Direction::Ascending,
         )?
         .next()
         .unwrap()?;
@@ -8,7 +8,7 @@ use axum::{
     response::IntoResponse,
     routing::{delete, get, post},
 };
-use kivis::{MemoryStorage, Repository};
+use kivis::{Direction, MemoryStorage, Repository};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 
@@ -48,7 +48,7 @@ pub fn create_router(storage: MemoryStorage) -> Router {
         .route("/insert", post(insert_handler))
         .route("/get/:key", get(get_handler))
         .route("/remove/:key", delete(remove_handler))
-        .route("/keys/:start/:end", get(keys_handler))
+        .route("/keys/:start/:end/:order", get(keys_handler))
         .with_state(shared_storage)
 }
 
@@ -144,7 +144,7 @@ async fn remove_handler(
 /// Handler for iterating keys in a range
 async fn keys_handler(
     State(storage): State<SharedStorage>,
-    Path((start, end)): Path<(String, String)>,
+    Path((start, end, order)): Path<(String, String, String)>,
 ) -> impl IntoResponse {
     // Decode hex-encoded keys
     let start_key = match hex::decode(&start) {
@@ -155,12 +155,17 @@ async fn keys_handler(
         Ok(k) => k,
         Err(_) => return (StatusCode::BAD_REQUEST, Json(KeysResponse { keys: vec![] })),
     };
+    let direction = match order.as_str() {
+        "asc" => Direction::Ascending,
+        "desc" => Direction::Descending,
+        _ => return (StatusCode::BAD_REQUEST, Json(KeysResponse { keys: vec![] })),
+    };
 
     let storage = storage.lock().unwrap();
 
     // Collect keys while we still hold the lock
     let keys_result: Result<Vec<String>, kivis::MemoryStorageError> = (|| {
-        let iter = Repository::scan_range(&*storage, start_key..end_key)?;
+        let iter = Repository::scan_range(&*storage, start_key..end_key, direction)?;
         let keys: Vec<String> = iter
             .filter_map(|result| result.ok())
             .map(|key| hex::encode(&key))
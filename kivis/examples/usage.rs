@@ -1,4 +1,4 @@
-use kivis::{Database, DatabaseError, DeriveKey, MemoryStorage, Record, manifest};
+use kivis::{Database, DatabaseError, DeriveKey, Direction, MemoryStorage, Record, manifest};
 
 /// A user record with an indexed name field
 #[derive(
@@ -80,7 +80,10 @@ fn main() -> Result<(), DatabaseError<kivis::MemoryStorage>> {
 
     // Records can be retrieved by indexed name
     let users_named_bob = store
-        .iter_by_index(UserNameIndex("Bob".into())..UserNameIndex("Boba".into()))?
+        .iter_by_index(
+            UserNameIndex("Bob".into())..UserNameIndex("Boba".into()),
+            Direction::Ascending,
+        )?
         .collect::<Result<Vec<_>, _>>()?;
     assert_eq!(users_named_bob, vec![bob_key]);
 
@@ -89,6 +92,7 @@ fn main() -> Result<(), DatabaseError<kivis::MemoryStorage>> {
         .iter_by_index(
             PetFavouriteToyIndex(ToyKey(ToyKind::Ball, 7))
                 ..PetFavouriteToyIndex(ToyKey(ToyKind::Ball, 8)),
+            Direction::Ascending,
         )?
         .next()
         .unwrap()?;
@@ -1,24 +1,57 @@
 use crate::errors::DatabaseError;
-use crate::traits::{DatabaseEntry, Index, Storage};
+use crate::errors::{SchemaDiff, TableChange};
+use crate::traits::{DatabaseEntry, EnvelopeHeader, Index, Storage};
 use crate::transaction::DatabaseTransaction;
-use crate::wrap::{Subtable, WrapPrelude, empty_wrap, wrap};
+use crate::wrap::{
+    ReservedEntry, Subtable, WrapPrelude, empty_wrap, reserved_wrap, scope_wrap, sequence_wrap,
+    wrap,
+};
 use crate::{
-    AsKey, BufferOverflowOr, Cache, CacheAccess, CacheContainer, DeriveKey, Incrementable,
-    Manifest, Manifests, NoCache, RecordKey, Repository, Unified, Unifier, UnifierPair,
+    ApplyError, AsKey, BatchOp, BufferOverflowOr, Cache, CacheAccess, CacheContainer,
+    CoveringIndex, DeriveKey, Direction, Envelope, Incrementable, IndexPrefix, KeyPrefix, Manifest,
+    Manifests, NoCache, OrderPreserving, RecordKey, Repository, Scope, TransactionError, Unified,
+    Unifier, UnifierPair, UniqueIndex,
 };
-use core::ops::Range;
-use serde::de::DeserializeOwned;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use core::cmp::Ordering;
+use core::ops::{Bound, Range, RangeBounds};
+use serde::{Serialize, de::DeserializeOwned};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
 
 type StorageKU<S> = <<S as Storage>::Unifiers as UnifierPair>::KeyUnifier;
+type StorageVU<S> = <<S as Storage>::Unifiers as UnifierPair>::ValueUnifier;
+type StorageKeyBuffer<S> = <StorageKU<S> as Unifier>::D;
 
 type DatabaseIteratorItem<R, S> = Result<<R as DatabaseEntry>::Key, DatabaseError<S>>;
 
+type DatabaseRecordItem<R, S> = Result<(<R as DatabaseEntry>::Key, R), DatabaseError<S>>;
+
+type DatabaseProjectionItem<I, S> =
+    Result<(<I as Index>::Key, <I as CoveringIndex>::Projection), DatabaseError<S>>;
+
+/// The most records or index entries written by each transaction of [`Database::migrate_records`].
+pub const MIGRATION_BATCH: usize = 1024;
+
+/// Returns whether a fingerprint describes values stored in [`Envelope`]s.
+fn enveloped(fingerprint: &str) -> bool {
+    fingerprint.split("; ").any(|part| part == "envelope")
+}
+
+type StorageEntry<S> = Result<
+    (<StorageKU<S> as Unifier>::D, <StorageVU<S> as Unifier>::D),
+    <<S as Storage>::Repo as Repository>::Error,
+>;
+
 /// The `kivis` database type. All interactions with the database are done through this type.
 pub struct Database<S: Storage, M: Manifest<S::Unifiers>, C: Cache = NoCache> {
     pub(crate) storage: S,
     pub(crate) manifest: M,
     pub(crate) unifiers: S::Unifiers,
     pub(crate) cache: C,
+    /// Scopes whose records have to be migrated before they can be accessed, with their stored version.
+    pub(crate) pending: Vec<(u8, u32)>,
 }
 
 impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
@@ -33,6 +66,7 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
             manifest: M::default(),
             unifiers: S::Unifiers::default(),
             cache: C::default(),
+            pending: Vec::new(),
         };
         let mut manifest = M::default();
         manifest.load(&mut db)?;
@@ -40,6 +74,258 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         Ok(db)
     }
 
+    /// Compares the fingerprint stored for each `(scope, version, indexes, fingerprint)` table with the given one,
+    /// storing the fingerprint of tables that don't have one yet, and the version and index count of those that
+    /// are also empty.
+    ///
+    /// Called on open by the manifests of [`manifest!`](crate::manifest), with the [`VERSION`](DatabaseEntry::VERSION),
+    /// [`INDEX_COUNT_HINT`](DatabaseEntry::INDEX_COUNT_HINT) and [`FINGERPRINT`](DatabaseEntry::FINGERPRINT)
+    /// of each member. Tables stored at an older version aren't compared, they're updated by [`Self::migrate`],
+    /// as are tables holding records without a stored version. Until then their records and indexes can't be
+    /// accessed, unless their values are stored in [`Envelope`]s both before and after, which carry their own
+    /// versions. Empty fingerprints aren't checked.
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::SchemaMismatch`] with the differences if a stored fingerprint differs or a table
+    /// was written by a newer version, in which case nothing is written, or a [`DatabaseError`] if reading or
+    /// writing the fingerprints fails.
+    pub fn check_schema(&mut self, tables: &[(u8, u32, u8, &str)]) -> Result<(), DatabaseError<S>> {
+        let mut changes = Vec::new();
+        let mut missing: Vec<BatchOp<S::Unifiers>> = Vec::new();
+        let mut pending = Vec::new();
+        for &(scope, version, indexes, fingerprint) in
+            tables.iter().filter(|(_, _, _, f)| !f.is_empty())
+        {
+            let schema_key = self.reserved_key(scope, &ReservedEntry::Schema)?;
+            let Some(stored) = self.read_reserved::<String>(&schema_key)? else {
+                missing.push(BatchOp::Insert {
+                    key: schema_key,
+                    value: self.reserved_value(&fingerprint)?,
+                });
+                // Tables without a stored version are at version 1, and those without an index count
+                // have unknown indexes, so records stored before they were recorded are left to be migrated.
+                if self.scope_is_empty(scope)? {
+                    if version != 1 {
+                        missing.push(BatchOp::Insert {
+                            key: self.reserved_key(scope, &ReservedEntry::Version)?,
+                            value: self.reserved_value(&version)?,
+                        });
+                    }
+                    missing.push(BatchOp::Insert {
+                        key: self.reserved_key(scope, &ReservedEntry::Indexes)?,
+                        value: self.reserved_value(&indexes)?,
+                    });
+                } else if version != 1 {
+                    pending.push((scope, 1));
+                }
+                continue;
+            };
+            let stored_version = self.stored_version(scope)?;
+            match stored_version.cmp(&version) {
+                Ordering::Less => {
+                    if !(enveloped(&stored) && enveloped(fingerprint)) {
+                        pending.push((scope, stored_version));
+                    }
+                }
+                Ordering::Equal => {
+                    changes.extend(TableChange::between(scope, &stored, fingerprint));
+                }
+                Ordering::Greater => {
+                    let mut change =
+                        TableChange::between(scope, &stored, fingerprint).unwrap_or(TableChange {
+                            scope,
+                            removed: Vec::new(),
+                            added: Vec::new(),
+                        });
+                    change
+                        .removed
+                        .insert(0, format!("version {stored_version}"));
+                    change.added.insert(0, format!("version {version}"));
+                    changes.push(change);
+                }
+            }
+        }
+        if !changes.is_empty() {
+            return Err(DatabaseError::SchemaMismatch(SchemaDiff {
+                tables: changes,
+            }));
+        }
+        self.pending
+            .retain(|(scope, _)| !tables.iter().any(|table| table.0 == *scope));
+        self.pending.extend(pending);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        self.storage
+            .repository_mut()
+            .apply(missing.into_iter().map(Ok::<_, core::convert::Infallible>))
+            .map_err(|e| match e {
+                ApplyError::Serialization(never) => match never {},
+                ApplyError::Application(storage_err) => DatabaseError::Storage(storage_err),
+            })
+    }
+
+    /// Migrates the records of every type of the manifest stored at an older version than the current one,
+    /// returning how many records were rewritten.
+    ///
+    /// Each type is migrated in bounded transactions of its own, see [`Self::migrate_records`].
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::UnsupportedVersion`] if records of a type can't be converted from their version,
+    /// or a [`DatabaseError`] if reading or writing the records fails. Types migrated before the error stay migrated.
+    pub fn migrate(&mut self) -> Result<usize, DatabaseError<S>>
+    where
+        S::Unifiers: 'static,
+    {
+        M::migrate(self)
+    }
+
+    /// Migrates the records of the given type to its current [`VERSION`](DatabaseEntry::VERSION) if they're stored
+    /// at an older one, returning how many records were rewritten.
+    ///
+    /// The records are converted with [`DatabaseEntry::upgrade`] and rewritten, the index entries of the previous
+    /// version are replaced with new ones, and the version, index count and fingerprint of the type are recorded.
+    /// Only the indexes counted for the previous version are cleared, or all possible ones for tables whose
+    /// index count wasn't recorded.
+    /// Values in [`Envelope`]s are converted from their own versions, and tables are rewritten when
+    /// `#[record(envelope)]` is added or removed together with a new version.
+    ///
+    /// Writes are committed in transactions of at most [`MIGRATION_BATCH`] records or index entries, the version,
+    /// index count and fingerprint with the last one, and the records of each batch are converted before it's
+    /// written. A migration that was interrupted carries on from the last committed record when it's run again,
+    /// until then the records of the type return [`DatabaseError::PendingMigration`].
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::UnsupportedVersion`] if the records can't be converted from their version,
+    /// or a [`DatabaseError`] if reading or writing the records fails. Nothing is written if the version of the
+    /// table can't be converted, while values in [`Envelope`]s carry their own versions, so the batches before
+    /// one holding an unsupported value stay rewritten.
+    pub fn migrate_records<R>(&mut self) -> Result<usize, DatabaseError<S>>
+    where
+        S::Unifiers: 'static,
+        R: DatabaseEntry + 'static,
+        R::Key: RecordKey<Record = R> + 'static,
+        for<'f> &'f (R::Key, R): Into<M::Record<'f>>,
+        M: Manifests<R>,
+    {
+        let version = self.stored_version(R::SCOPE)?;
+        let enveloped = self.stored_enveloped(R::SCOPE)?;
+        if version >= R::VERSION && enveloped == R::ENVELOPE {
+            return Ok(0);
+        }
+        let progress_key = self.reserved_key(R::SCOPE, &ReservedEntry::Migration)?;
+        let indexes_key = self.reserved_key(R::SCOPE, &ReservedEntry::Indexes)?;
+        let mut last = self.read_reserved::<R::Key>(&progress_key)?;
+        // The indexes of the previous version are cleared before the first batch is written,
+        // after which the progress of the migration is stored.
+        let mut cleared = last.is_some();
+
+        let mut count = 0;
+        loop {
+            let mut batch = Vec::new();
+            for entry in self.migration_entries::<R>(last.as_ref(), MIGRATION_BATCH)? {
+                let (key, value) = entry.map_err(DatabaseError::Storage)?;
+                let key: R::Key = self
+                    .unifiers
+                    .key_unifier()
+                    .deserialize_wrapped(&key)
+                    .map_err(DatabaseError::KeyDeserialization)?;
+                let (record, _) = self.decode_record::<R>(&value, enveloped, version)?;
+                batch.push((key, record));
+            }
+            if !cleared {
+                let indexes = self.read_reserved(&indexes_key)?.unwrap_or(u8::MAX - 1);
+                for discriminator in 0..indexes {
+                    self.clear_index::<R>(discriminator)?;
+                }
+                cleared = true;
+            }
+
+            let rewritten = batch.len();
+            let mut transaction = self.create_transaction();
+            for (key, record) in batch {
+                transaction.rewrite(key.clone(), record)?;
+                last = Some(key);
+            }
+            count += rewritten;
+            if rewritten < MIGRATION_BATCH {
+                transaction.delete_entry(progress_key, false);
+                transaction.set_reserved(
+                    self.reserved_key(R::SCOPE, &ReservedEntry::Version)?,
+                    self.reserved_value(&R::VERSION)?,
+                );
+                // Types without a fingerprint are implemented by hand and may have more indexes than hinted.
+                if R::FINGERPRINT.is_empty() {
+                    transaction.delete_entry(indexes_key, false);
+                } else {
+                    transaction
+                        .set_reserved(indexes_key, self.reserved_value(&R::INDEX_COUNT_HINT)?);
+                    transaction.set_reserved(
+                        self.reserved_key(R::SCOPE, &ReservedEntry::Schema)?,
+                        self.reserved_value(&R::FINGERPRINT)?,
+                    );
+                }
+                self.commit(transaction)?;
+                self.pending.retain(|(scope, _)| *scope != R::SCOPE);
+                return Ok(count);
+            }
+            if let Some(last) = &last {
+                transaction.set_reserved(progress_key.clone(), self.reserved_value(last)?);
+            }
+            self.commit(transaction)?;
+        }
+    }
+
+    /// Scans up to `limit` stored records of the given type following the key `after`, or from the first one.
+    fn migration_entries<R: DatabaseEntry>(
+        &self,
+        after: Option<&R::Key>,
+        limit: usize,
+    ) -> Result<impl Iterator<Item = StorageEntry<S>> + use<'_, R, S, M, C>, DatabaseError<S>> {
+        let start = after.map_or(Bound::Unbounded, Bound::Excluded);
+        let range = self.key_range::<R>(&(start, Bound::Unbounded))?;
+        let entries = self
+            .storage
+            .repository()
+            .scan_range_entries(range, Direction::Ascending)
+            .map_err(DatabaseError::Storage)?;
+        Ok(entries.take(limit))
+    }
+
+    /// Deletes the entries of the index with the given discriminator of a type, [`MIGRATION_BATCH`] per transaction.
+    fn clear_index<R: DatabaseEntry>(&mut self, discriminator: u8) -> Result<(), DatabaseError<S>>
+    where
+        S::Unifiers: 'static,
+    {
+        let mut start = StorageKeyBuffer::<S>::default();
+        self.unifiers
+            .key_unifier()
+            .serialize(
+                &mut start,
+                &WrapPrelude::new::<R>(Subtable::Index(discriminator)),
+            )
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        let mut end = start.clone();
+        end.next()
+            .map_err(|e| DatabaseError::from_buffer_overflow_or(BufferOverflowOr::overflow(e)))?;
+        let unique = R::UNIQUE_INDEXES.contains(&discriminator);
+        loop {
+            let mut transaction = self.create_transaction();
+            let entries = self
+                .storage
+                .repository()
+                .scan_range(start.clone()..end.clone(), Direction::Ascending)
+                .map_err(DatabaseError::Storage)?;
+            for key in entries.take(MIGRATION_BATCH) {
+                transaction.delete_entry(key.map_err(DatabaseError::Storage)?, unique);
+            }
+            if transaction.is_empty() {
+                return Ok(());
+            }
+            self.commit(transaction)?;
+        }
+    }
+
     pub fn with_unifiers(&mut self, unifiers: S::Unifiers) {
         self.unifiers = unifiers;
     }
@@ -51,7 +337,8 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
     /// For records that do not have an autoincremented key, use [`Self::insert`] instead.
     /// # Errors
     ///
-    /// Returns a [`DatabaseError`] if serializing or writing the record fails.
+    /// Returns [`DatabaseError::UniqueViolation`] if another record owns a value of one of the record's unique indexes,
+    /// or a [`DatabaseError`] if serializing or writing the record fails.
     pub fn put<R>(&mut self, record: R) -> Result<R::Key, DatabaseError<S>>
     where
         S::Unifiers: 'static,
@@ -61,6 +348,7 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         M: Manifests<R>,
         C: CacheAccess<R>,
     {
+        self.check_migrated(R::SCOPE)?;
         let mut transaction = self.create_transaction();
         let inserted_key = transaction.put(record, &mut self.manifest)?;
         self.commit(transaction)?;
@@ -68,32 +356,160 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         Ok(inserted_key)
     }
 
+    /// Adds many records with autoincremented keys in a single transaction, together with their index entries.
+    ///
+    /// Consecutive keys are assigned in iteration order, and returned in the same order
+    /// in any collection implementing [`Extend`], such as a `Vec`.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if incrementing a key, serializing or writing the records fails.
+    /// No record is written if an error occurs.
+    pub fn put_many<R, B>(
+        &mut self,
+        records: impl IntoIterator<Item = R>,
+    ) -> Result<B, DatabaseError<S>>
+    where
+        S::Unifiers: 'static,
+        R: DatabaseEntry + Clone + 'static,
+        R::Key: RecordKey<Record = R> + Incrementable + Ord + 'static,
+        for<'f> &'f (R::Key, R): Into<M::Record<'f>>,
+        M: Manifests<R>,
+        C: CacheAccess<R>,
+        B: Default + Extend<R::Key>,
+    {
+        self.check_migrated(R::SCOPE)?;
+        let mut transaction = self.create_transaction();
+        let mut keys = B::default();
+        for record in records {
+            let key = transaction.put(record, &mut self.manifest)?;
+            self.cache.access().expire(&key);
+            keys.extend(Some(key));
+        }
+        self.commit(transaction)?;
+        Ok(keys)
+    }
+
     /// Inserts a record with a derived key into the database, together with all related index entries.
     ///
     /// The record must implement the [`DatabaseEntry`] trait, with the key type implementing the [`RecordKey`] trait pointing back to it.
     /// The record's key must implement the [`DeriveKey`] trait, returning the key type.
     /// For records that don't store keys internally, use [`Self::put`] instead.
+    ///
+    /// If a record is already stored under the same key, it is replaced and its stale index entries are removed.
     /// # Errors
     ///
-    /// Returns a [`DatabaseError`] if serializing or writing the record fails.
+    /// Returns [`DatabaseError::UniqueViolation`] if another record owns a value of one of the record's unique indexes,
+    /// or a [`DatabaseError`] if serializing or writing the record fails.
     pub fn insert<K, R>(&mut self, record: R) -> Result<K, DatabaseError<S>>
     where
         S::Unifiers: 'static,
         K: RecordKey<Record = R> + 'static,
         R: DeriveKey<Key = K> + DatabaseEntry<Key = K> + Clone + 'static,
         for<'f> &'f (K, R): Into<M::Record<'f>>,
+        for<'f> &'f (K, R, R): Into<M::Record<'f>>,
         M: Manifests<R>,
         C: CacheAccess<R>,
     {
+        let key = R::key(&record);
+        let previous = self.load_record(&key)?;
         let mut transaction = self.create_transaction();
-        let inserted_key = transaction
-            .insert::<K, R>(record)
-            .map_err(DatabaseError::from_transaction_error)?;
+        let inserted_key = match previous {
+            Some(previous) => transaction
+                .replace(key.clone(), record, previous)
+                .map(|()| key),
+            None => transaction.insert::<K, R>(record),
+        }
+        .map_err(DatabaseError::from_transaction_error)?;
         self.commit(transaction)?;
         self.cache.access().expire(&inserted_key);
         Ok(inserted_key)
     }
 
+    /// Inserts many records with derived keys in a single transaction, together with their index entries.
+    ///
+    /// Records already stored under the same key are replaced as with [`Self::insert`], including
+    /// records written earlier in the same batch. The keys are returned in iteration order
+    /// in any collection implementing [`Extend`], such as a `Vec`.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if reading a previous version, serializing or writing the records fails.
+    /// No record is written if an error occurs.
+    pub fn insert_many<K, R, B>(
+        &mut self,
+        records: impl IntoIterator<Item = R>,
+    ) -> Result<B, DatabaseError<S>>
+    where
+        S::Unifiers: 'static,
+        K: RecordKey<Record = R> + Ord + 'static,
+        R: DeriveKey<Key = K> + DatabaseEntry<Key = K> + Clone + 'static,
+        for<'f> &'f (K, R): Into<M::Record<'f>>,
+        for<'f> &'f (K, R, R): Into<M::Record<'f>>,
+        M: Manifests<R>,
+        C: CacheAccess<R>,
+        B: Default + Extend<K>,
+    {
+        let mut transaction = self.create_transaction();
+        // Latest version of every key written by this batch, which later duplicates replace.
+        let mut written = BTreeMap::new();
+        let mut keys = B::default();
+        for record in records {
+            let key = R::key(&record);
+            let previous = match written.remove(&key) {
+                Some(previous) => Some(previous),
+                None => self.load_record(&key)?,
+            };
+            written.insert(key.clone(), record.clone());
+            match previous {
+                Some(previous) => transaction.replace(key.clone(), record, previous),
+                None => transaction.insert::<K, R>(record).map(|_| ()),
+            }
+            .map_err(DatabaseError::from_transaction_error)?;
+            self.cache.access().expire(&key);
+            keys.extend(Some(key));
+        }
+        self.commit(transaction)?;
+        Ok(keys)
+    }
+
+    /// Updates a stored record in place with the provided closure.
+    ///
+    /// The record is loaded, modified by `f` and written back together with its changed index entries in a single transaction.
+    /// Returns `false` without calling `f` if no record is stored under the key.
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::DerivedKeyChanged`] if `f` changes the key derived from the record,
+    /// or a [`DatabaseError`] if reading, serializing or writing the record fails.
+    pub fn update<R>(
+        &mut self,
+        key: &R::Key,
+        f: impl FnOnce(&mut R),
+    ) -> Result<bool, DatabaseError<S>>
+    where
+        S::Unifiers: 'static,
+        R: DatabaseEntry + Clone + 'static,
+        R::Key: RecordKey<Record = R> + 'static,
+        for<'f> &'f (R::Key, R, R): Into<M::Record<'f>>,
+        M: Manifests<R>,
+        C: CacheAccess<R>,
+    {
+        let Some(previous) = self.load_record::<R>(key)? else {
+            return Ok(false);
+        };
+        let mut record = previous.clone();
+        f(&mut record);
+        if record.derived_key().is_some_and(|derived| derived != *key) {
+            return Err(DatabaseError::DerivedKeyChanged);
+        }
+
+        let mut transaction = self.create_transaction();
+        transaction
+            .replace(key.clone(), record, previous)
+            .map_err(DatabaseError::from_transaction_error)?;
+        self.commit(transaction)?;
+        self.cache.access().expire(key);
+        Ok(true)
+    }
+
     pub fn create_transaction(&self) -> DatabaseTransaction<M, S::Unifiers>
     where
         S::Unifiers: 'static,
@@ -123,6 +539,7 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
     ///
     /// The record must implement the [`DatabaseEntry`] trait, with the key type implementing the [`RecordKey`] trait pointing back to it.
     /// If the record is not found, `None` is returned.
+    /// Values in an [`Envelope`] written by an older version are converted, see [`Self::get_upgraded`].
     /// # Errors
     ///
     /// Returns a [`DatabaseError`] if the key cannot be serialized, if IO fails,
@@ -142,34 +559,143 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
             return Ok(Some(cached));
         }
 
-        let mut serialized_key = <StorageKU<S> as Unifier>::D::default();
+        let Some(record) = self.load_record(key)? else {
+            return Ok(None);
+        };
+        self.cache.access().set(key, &record);
+        Ok(Some(record))
+    }
+
+    /// Retrieves a record from the database by its key, like [`Self::get`], writing it back if it was stored
+    /// by an older version.
+    ///
+    /// Values of records declared with `#[record(envelope)]` are converted on every read until they're
+    /// rewritten, this makes the conversion permanent one record at a time, as an alternative to [`Self::migrate`].
+    /// The record is read from storage even if it's cached. Index entries are written for the converted record,
+    /// but entries of the older version whose values differ are left in place, [`Self::migrate`] rebuilds them.
+    /// # Errors
+    ///
+    /// Returns [`DatabaseError::UnsupportedVersion`] if the record can't be converted from its version,
+    /// or a [`DatabaseError`] if reading, serializing or writing the record fails.
+    pub fn get_upgraded<R>(&mut self, key: &R::Key) -> Result<Option<R>, DatabaseError<S>>
+    where
+        S::Unifiers: 'static,
+        R: DatabaseEntry + Clone + 'static,
+        R::Key: RecordKey<Record = R> + 'static,
+        for<'f> &'f (R::Key, R): Into<M::Record<'f>>,
+        M: Manifests<R>,
+        C: CacheAccess<R>,
+    {
+        let Some((record, upgraded)) = self.read_record::<R>(key)? else {
+            return Ok(None);
+        };
+        if upgraded {
+            let mut transaction = self.create_transaction();
+            transaction.rewrite(key.clone(), record.clone())?;
+            self.commit(transaction)?;
+        }
+        self.cache.access().set(key, &record);
+        Ok(Some(record))
+    }
 
+    /// Checks whether a record is stored under the given key, without reading or deserializing it.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if the key cannot be serialized or if the underlying storage fails.
+    pub fn contains<Q: AsKey>(&self, key: &Q) -> Result<bool, DatabaseError<S>>
+    where
+        Q::Key: RecordKey,
+        <Q::Key as RecordKey>::Record: DatabaseEntry<Key = Q::Key>,
+        M: Manifests<<Q::Key as RecordKey>::Record>,
+    {
+        let mut serialized_key = StorageKeyBuffer::<S>::default();
         wrap::<<Q::Key as RecordKey>::Record, StorageKU<S>>(
-            key,
+            key.as_key(),
             &self.unifiers.key_unifier(),
             &mut serialized_key,
         )
         .map_err(DatabaseError::from_buffer_overflow_or)?;
 
-        let raw_key = serialized_key.as_view();
+        self.storage
+            .repository()
+            .contains_entry(serialized_key.as_view())
+            .map_err(DatabaseError::Storage)
+    }
+
+    /// Reads a record straight from storage, bypassing the cache.
+    fn load_record<R>(&self, key: &R::Key) -> Result<Option<R>, DatabaseError<S>>
+    where
+        R: DatabaseEntry,
+        R::Key: RecordKey<Record = R>,
+        M: Manifests<R>,
+    {
+        Ok(self.read_record(key)?.map(|(record, _)| record))
+    }
+
+    /// Reads a record straight from storage, together with whether it was converted from an older version.
+    fn read_record<R>(&self, key: &R::Key) -> Result<Option<(R, bool)>, DatabaseError<S>>
+    where
+        R: DatabaseEntry,
+        R::Key: RecordKey<Record = R>,
+        M: Manifests<R>,
+    {
+        self.check_migrated(R::SCOPE)?;
+        let mut serialized_key = <StorageKU<S> as Unifier>::D::default();
+
+        wrap::<R, StorageKU<S>>(key, &self.unifiers.key_unifier(), &mut serialized_key)
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
 
         let Some(value) = self
             .storage
             .repository()
-            .get_entry(raw_key)
+            .get_entry(serialized_key.as_view())
             .map_err(DatabaseError::Storage)?
         else {
             return Ok(None);
         };
 
-        let record = self
-            .unifiers
-            .value_unifier()
-            .deserialize(&value)
-            .map_err(DatabaseError::ValueDeserialization)?;
+        self.decode_record(&value, R::ENVELOPE, R::VERSION)
+            .map(Some)
+    }
 
-        self.cache.access().set(key, &record);
-        Ok(Some(record))
+    /// Deserializes a stored value of a record, converting it if it was written by an older version.
+    ///
+    /// Values in an [`Envelope`] carry their version, others are read at the `version` of their table.
+    /// Returns whether the value was converted.
+    fn decode_record<R: DatabaseEntry>(
+        &self,
+        value: &<StorageVU<S> as Unifier>::D,
+        enveloped: bool,
+        version: u32,
+    ) -> Result<(R, bool), DatabaseError<S>> {
+        let value_unifier = self.unifiers.value_unifier();
+        let version = if enveloped {
+            value_unifier
+                .deserialize::<EnvelopeHeader>(value)
+                .map_err(DatabaseError::ValueDeserialization)?
+                .version
+        } else {
+            version
+        };
+        if version != R::VERSION {
+            let record = R::upgrade(version, value, &value_unifier, enveloped)
+                .map_err(DatabaseError::ValueDeserialization)?
+                .ok_or(DatabaseError::UnsupportedVersion {
+                    scope: R::SCOPE,
+                    version,
+                })?;
+            return Ok((record, true));
+        }
+        let record = if enveloped {
+            value_unifier
+                .deserialize::<Envelope<R>>(value)
+                .map(|envelope| envelope.payload)
+        } else {
+            value_unifier.deserialize(value)
+        };
+        record
+            .map(|record| (record, false))
+            .map_err(DatabaseError::ValueDeserialization)
     }
 
     /// Removes a record from the database by its key and returns it.
@@ -202,17 +728,39 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         Ok(())
     }
 
-    /// Iterates over all keys in the database within the specified range.
+    /// Iterates over all keys in the database within the specified range, in the given direction.
     ///
-    /// The range is inclusive of the start and exclusive of the end.
+    /// Any [`RangeBounds`] can be used, so inclusive, open-ended and full (`..`) ranges are supported.
     /// The keys must implement the [`RecordKey`] trait, and the related [`DatabaseEntry`] must point back to it.
+    /// The bounds are compared on the encoded keys, so the key unifier must be [`OrderPreserving`].
     /// # Errors
     ///
     /// Returns a [`DatabaseError`] if serializing the range bounds fails or if the
     /// underlying storage iterator errors.
     pub fn iter_keys<K: RecordKey + Ord>(
         &self,
-        range: Range<K>,
+        range: impl RangeBounds<K>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = DatabaseIteratorItem<K::Record, S>>, DatabaseError<S>>
+    where
+        K::Record: DatabaseEntry<Key = K>,
+        M: Manifests<K::Record>,
+        StorageKU<S>: OrderPreserving,
+    {
+        self.scan_keys::<K>(self.key_range::<K::Record>(&range)?, direction)
+    }
+
+    /// Iterates over all keys of a record type, in the given direction.
+    ///
+    /// Keys are ordered by their encoding, which follows [`Ord`] only for an [`OrderPreserving`] key unifier.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if serializing the range bounds fails or if the
+    /// underlying storage iterator errors.
+    pub fn iter_all_keys<K: RecordKey + Ord>(
+        &self,
+        direction: Direction,
     ) -> Result<
         impl Iterator<Item = DatabaseIteratorItem<K::Record, S>> + use<'_, K, S, M, C>,
         DatabaseError<S>,
@@ -221,73 +769,136 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         K::Record: DatabaseEntry<Key = K>,
         M: Manifests<K::Record>,
     {
-        let mut start = <StorageKU<S> as Unifier>::D::default();
-        wrap::<K::Record, StorageKU<S>>(&range.start, &self.unifiers.key_unifier(), &mut start)
-            .map_err(DatabaseError::from_buffer_overflow_or)?;
-        let mut end = <StorageKU<S> as Unifier>::D::default();
-        wrap::<K::Record, StorageKU<S>>(&range.end, &self.unifiers.key_unifier(), &mut end)
-            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        self.scan_keys::<K>(self.key_range::<K::Record>(&..)?, direction)
+    }
+
+    /// Iterates over all keys that start with the given leading key components, in the given direction.
+    ///
+    /// For a record keyed by several fields, such as `(tenant_id, user_id)`, a prefix of `&(tenant_id,)`
+    /// yields the keys of all users of that tenant. Valid prefixes implement [`KeyPrefix`] for the key type.
+    /// The prefix is matched on the encoded keys, so the key unifier must be [`OrderPreserving`].
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if serializing the prefix fails or if the
+    /// underlying storage iterator errors.
+    pub fn iter_keys_with_prefix<K, P>(
+        &self,
+        prefix: &P,
+        direction: Direction,
+    ) -> Result<
+        impl Iterator<Item = DatabaseIteratorItem<K::Record, S>> + use<'_, K, P, S, M, C>,
+        DatabaseError<S>,
+    >
+    where
+        K: RecordKey + Ord,
+        K::Record: DatabaseEntry<Key = K>,
+        P: KeyPrefix<K>,
+        M: Manifests<K::Record>,
+        StorageKU<S>: OrderPreserving,
+    {
+        let main_prefix = self.main_prefix::<K::Record>()?;
+        let start = self.bound_buffer(&main_prefix, prefix, false)?;
+        let end = self.bound_buffer(&main_prefix, prefix, true)?;
 
         let raw_iter = self
             .storage
             .repository()
-            .scan_range(start..end)
+            .scan_range(start..end, direction)
             .map_err(DatabaseError::Storage)?;
 
-        Ok(raw_iter.map(|elem| {
-            let value = match elem {
-                Ok(value) => value,
-                Err(e) => return Err(DatabaseError::Storage(e)),
-            };
-
-            let key: K = self
-                .unifiers
-                .key_unifier()
-                .deserialize_wrapped(&value)
-                .map_err(DatabaseError::KeyDeserialization)?;
+        Ok(raw_iter.map(|elem| self.process_key_result(elem)))
+    }
 
-            Ok(key)
-        }))
+    /// Iterates over all records in the database within the specified range, together with their keys,
+    /// in the given direction.
+    ///
+    /// Any [`RangeBounds`] can be used, as with [`Self::iter_keys`].
+    /// Unlike calling [`Self::get`] for every key returned by [`Self::iter_keys`], the values are
+    /// read during the scan, using [`Repository::scan_range_entries`].
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if serializing the range bounds fails or if the
+    /// underlying storage iterator errors.
+    pub fn iter_records<R>(
+        &self,
+        range: impl RangeBounds<R::Key>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = DatabaseRecordItem<R, S>>, DatabaseError<S>>
+    where
+        R: DatabaseEntry,
+        R::Key: RecordKey<Record = R> + Ord,
+        M: Manifests<R>,
+        StorageKU<S>: OrderPreserving,
+    {
+        self.scan_records::<R>(self.key_range::<R>(&range)?, direction)
     }
 
+    /// Iterates over all records of the given type, together with their keys, in the given direction.
+    ///
+    /// As with [`Self::get`], values in an [`Envelope`] written by an older version are converted.
     /// # Errors
     ///
     /// Returns a [`DatabaseError`] if serializing the range bounds fails or if the
     /// underlying storage iterator errors.
-    pub fn iter_all_keys<K: RecordKey + Ord>(
+    pub fn iter_all_records<R>(
         &self,
+        direction: Direction,
     ) -> Result<
-        impl Iterator<Item = DatabaseIteratorItem<K::Record, S>> + use<'_, K, S, M, C>,
+        impl Iterator<Item = DatabaseRecordItem<R, S>> + use<'_, R, S, M, C>,
         DatabaseError<S>,
     >
+    where
+        R: DatabaseEntry,
+        R::Key: RecordKey<Record = R> + Ord,
+        M: Manifests<R>,
+    {
+        self.scan_records::<R>(self.key_range::<R>(&..)?, direction)
+    }
+
+    /// Counts the records of the given type with keys in the specified range, without reading their values.
+    ///
+    /// Any [`RangeBounds`] can be used, a full range (`..`) counts all records of the type.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if serializing the range bounds fails or if the
+    /// underlying storage errors.
+    pub fn count<R>(&self, range: impl RangeBounds<R::Key>) -> Result<usize, DatabaseError<S>>
+    where
+        R: DatabaseEntry,
+        R::Key: RecordKey<Record = R>,
+        M: Manifests<R>,
+        StorageKU<S>: OrderPreserving,
+    {
+        self.storage
+            .repository()
+            .count_range(self.key_range::<R>(&range)?)
+            .map_err(DatabaseError::Storage)
+    }
+
+    /// Returns the last key assigned by [`Self::put`] to records of the given type, if any.
+    ///
+    /// The key is read from the type's persisted sequence with a single lookup,
+    /// it remains set after the record holding it is removed.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if reading or deserializing the sequence fails.
+    pub fn sequence<K: RecordKey>(&self) -> Result<Option<K>, DatabaseError<S>>
     where
         K::Record: DatabaseEntry<Key = K>,
         M: Manifests<K::Record>,
     {
-        let (start, end) = empty_wrap::<K::Record, StorageKU<S>>(&self.unifiers.key_unifier())
+        let mut serialized_key = StorageKeyBuffer::<S>::default();
+        sequence_wrap::<K::Record, _>(&self.unifiers.key_unifier(), &mut serialized_key)
             .map_err(DatabaseError::from_buffer_overflow_or)?;
-        let raw_iter = self
-            .storage
-            .repository()
-            .scan_range(start..end)
-            .map_err(DatabaseError::Storage)?;
-
-        Ok(raw_iter.map(|elem| {
-            let value = match elem {
-                Ok(value) => value,
-                Err(e) => return Err(DatabaseError::Storage(e)),
-            };
-
-            let key: K = self
-                .unifiers
-                .key_unifier()
-                .deserialize_wrapped(&value)
-                .map_err(DatabaseError::KeyDeserialization)?;
-
-            Ok(key)
-        }))
+        self.read_reserved(&serialized_key)
     }
 
+    /// Returns the greatest key stored for the given type, or the default key if there are none.
+    ///
+    /// Unlike [`Self::sequence`], this scans the stored records, so it doesn't account for removed ones.
+    /// It can be used to recover the sequence of databases written before sequences were persisted.
+    /// Keys are compared with [`Ord`], as encoded keys only sort like them for an [`OrderPreserving`] key unifier,
+    /// so every key of the type is read.
     /// # Errors
     ///
     /// Returns a [`DatabaseError`] if retrieving keys from the underlying storage fails.
@@ -296,14 +907,48 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         K::Record: DatabaseEntry<Key = K>,
         M: Manifests<K::Record>,
     {
-        let mut first = self.iter_all_keys::<K>()?;
+        self.iter_all_keys::<K>(Direction::Descending)?
+            .try_fold(K::default(), |last, key| Ok(last.max(key?)))
+    }
 
-        Ok(first.next().transpose()?.unwrap_or_default())
+    /// Returns the persisted sequence of the given type, recovering it with [`Self::last_id`] if it isn't stored.
+    ///
+    /// Called on open by the manifests of [`manifest!`](crate::manifest). A sequence recovered from the records
+    /// of a database written before sequences were persisted is stored, so later opens read it directly.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if reading the keys or reading or writing the sequence fails.
+    #[doc(hidden)]
+    pub fn load_sequence<K: RecordKey + Ord + Default>(&mut self) -> Result<K, DatabaseError<S>>
+    where
+        K::Record: DatabaseEntry<Key = K>,
+        M: Manifests<K::Record>,
+    {
+        if let Some(sequence) = self.sequence()? {
+            return Ok(sequence);
+        }
+        let last = self.last_id::<K>()?;
+        // Empty tables start from the default key, which needs no entry.
+        if last == K::default() {
+            return Ok(last);
+        }
+        let mut key = StorageKeyBuffer::<S>::default();
+        sequence_wrap::<K::Record, _>(&self.unifiers.key_unifier(), &mut key)
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        let value = self.reserved_value(&last)?;
+        self.storage
+            .repository_mut()
+            .insert_entry(key.as_view(), value.as_view())
+            .map_err(DatabaseError::Storage)?;
+        Ok(last)
     }
 
     /// Iterates over all index entries in the database within the specified range and returns their primary keys.
     ///
-    /// The range is inclusive of the start and exclusive of the end.
+    /// Entries are ordered by index value, then by primary key, in the given direction.
+    ///
+    /// Any [`RangeBounds`] can be used, bounds apply to the index values. A full range (`..`) scans the whole index.
+    /// As with [`Self::iter_keys`], the key unifier must be [`OrderPreserving`].
     /// The index must implement the [`Index`] trait.
     /// The returned iterator yields items of type `Result<Index::Record, DatabaseError<S>>`.
     /// # Errors
@@ -311,35 +956,43 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
     /// Returns a [`DatabaseError`] if the underlying storage iterator encounters an error.
     pub fn iter_by_index<I: Index + Ord>(
         &self,
-        range: Range<I>,
-    ) -> Result<
-        impl Iterator<Item = DatabaseIteratorItem<I::Record, S>> + use<'_, I, S, M, C>,
-        DatabaseError<S>,
-    > {
-        let mut start = <StorageKU<S> as Unifier>::D::default();
-        self.unifiers
-            .key_unifier()
-            .serialize(
-                &mut start,
-                &WrapPrelude::new::<I::Record>(Subtable::Index(I::INDEX)),
-            )
-            .map_err(DatabaseError::from_buffer_overflow_or)?;
-        let mut end = <StorageKU<S> as Unifier>::D::duplicate(start.as_view())
-            .map_err(|e| DatabaseError::from_buffer_overflow_or(BufferOverflowOr::overflow(e)))?;
+        range: impl RangeBounds<I>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = DatabaseIteratorItem<I::Record, S>>, DatabaseError<S>>
+    where
+        StorageKU<S>: OrderPreserving,
+    {
+        self.check_migrated(I::Record::SCOPE)?;
+        let raw_iter = self
+            .storage
+            .repository()
+            .scan_range_entries(self.index_range(&range)?, direction)
+            .map_err(DatabaseError::Storage)?;
 
-        self.unifiers
-            .key_unifier()
-            .serialize(&mut start, &range.start)
-            .map_err(DatabaseError::from_buffer_overflow_or)?;
-        self.unifiers
-            .key_unifier()
-            .serialize(&mut end, &range.end)
-            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        Ok(raw_iter.map(|elem| self.process_index_entry::<I::Record>(elem, I::INDEX)))
+    }
 
+    /// Iterates over the entries of a covering index within the specified range and returns the primary keys
+    /// together with the projections stored in the index, without reading the records.
+    ///
+    /// Entries are ordered as with [`Self::iter_by_index`].
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if serializing the range bounds fails or if the
+    /// underlying storage iterator errors.
+    pub fn iter_projection_by_index<I: CoveringIndex>(
+        &self,
+        range: impl RangeBounds<I>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = DatabaseProjectionItem<I, S>>, DatabaseError<S>>
+    where
+        StorageKU<S>: OrderPreserving,
+    {
+        self.check_migrated(I::Record::SCOPE)?;
         let raw_iter = self
             .storage
             .repository()
-            .scan_range(start..end)
+            .scan_range_entries(self.index_range(&range)?, direction)
             .map_err(DatabaseError::Storage)?;
 
         Ok(raw_iter.map(|elem| self.process_iter_result(elem)))
@@ -347,7 +1000,8 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
 
     /// Iterates over all index entries in the database that exactly match the given index key and returns their primary keys.
     ///
-    /// This function outputs multiple results since multiple records can share the same index key.
+    /// This function outputs multiple results since multiple records can share the same index key,
+    /// ordered from the largest primary key to the smallest.
     /// The index must implement the [`Index`] trait.
     /// The returned iterator yields items of type `Result<Index::Record, DatabaseError<S>>`.
     /// # Errors
@@ -360,6 +1014,7 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         impl Iterator<Item = DatabaseIteratorItem<I::Record, S>> + use<'_, I, S, M, C>,
         DatabaseError<S>,
     > {
+        self.check_migrated(I::Record::SCOPE)?;
         let index_prelude = WrapPrelude::new::<I::Record>(Subtable::Index(I::INDEX));
         let mut start = <StorageKU<S> as Unifier>::D::default();
         self.unifiers
@@ -379,10 +1034,99 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         let raw_iter = self
             .storage
             .repository()
-            .scan_range(start..end)
+            .scan_range_entries(start..end, Direction::Descending)
             .map_err(DatabaseError::Storage)?;
 
-        Ok(raw_iter.map(|elem| self.process_iter_result(elem)))
+        Ok(raw_iter.map(|elem| self.process_index_entry::<I::Record>(elem, I::INDEX)))
+    }
+
+    /// Iterates over all index entries whose values start with the given leading fields and returns their primary keys.
+    ///
+    /// For an index over `(status, created_at)`, a prefix of `&(status,)` yields the records with that status,
+    /// ordered by creation time in the given direction. Valid prefixes implement [`IndexPrefix`] for the index type.
+    /// As with [`Self::iter_by_index`], the key unifier must be [`OrderPreserving`].
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if serializing the prefix fails or if the
+    /// underlying storage iterator errors.
+    pub fn iter_by_index_with_prefix<I, P>(
+        &self,
+        prefix: &P,
+        direction: Direction,
+    ) -> Result<
+        impl Iterator<Item = DatabaseIteratorItem<I::Record, S>> + use<'_, I, P, S, M, C>,
+        DatabaseError<S>,
+    >
+    where
+        I: Index,
+        P: IndexPrefix<I>,
+        StorageKU<S>: OrderPreserving,
+    {
+        self.check_migrated(I::Record::SCOPE)?;
+        let index_prefix = self.index_prefix::<I>()?;
+        let start = self.bound_buffer(&index_prefix, prefix, false)?;
+        let end = self.bound_buffer(&index_prefix, prefix, true)?;
+
+        let raw_iter = self
+            .storage
+            .repository()
+            .scan_range_entries(start..end, direction)
+            .map_err(DatabaseError::Storage)?;
+
+        Ok(raw_iter.map(|elem| self.process_index_entry::<I::Record>(elem, I::INDEX)))
+    }
+
+    /// Retrieves the record owning the given value of a unique index.
+    ///
+    /// If no record holds the value, `None` is returned.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if the index value cannot be serialized, if IO fails,
+    /// or if deserializing the key or the record fails.
+    pub fn get_by_unique<I>(&mut self, value: &I) -> Result<Option<I::Record>, DatabaseError<S>>
+    where
+        I: UniqueIndex,
+        I::Key: RecordKey<Record = I::Record>,
+        I::Record: DatabaseEntry<Key = I::Key>,
+        M: Manifests<I::Record>,
+        C: CacheAccess<I::Record>,
+    {
+        self.check_migrated(I::Record::SCOPE)?;
+        let prefix = self.index_prefix::<I>()?;
+        let entry = self.bound_buffer(&prefix, value, false)?;
+
+        let Some(key) = self
+            .storage
+            .repository()
+            .get_entry(entry.as_view())
+            .map_err(DatabaseError::Storage)?
+        else {
+            return Ok(None);
+        };
+        let key = I::Record::index_owner(&key, I::INDEX, &self.unifiers.value_unifier())
+            .map_err(DatabaseError::ValueDeserialization)?;
+        self.get(&key)
+    }
+
+    /// Counts the index entries with values in the specified range, without reading the records.
+    ///
+    /// Any [`RangeBounds`] can be used, bounds apply to the index values.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if serializing the range bounds fails or if the
+    /// underlying storage errors.
+    pub fn count_by_index<I: Index>(
+        &self,
+        range: impl RangeBounds<I>,
+    ) -> Result<usize, DatabaseError<S>>
+    where
+        StorageKU<S>: OrderPreserving,
+    {
+        self.check_migrated(I::Record::SCOPE)?;
+        self.storage
+            .repository()
+            .count_range(self.index_range(&range)?)
+            .map_err(DatabaseError::Storage)
     }
 
     /// Consumes the database and returns the underlying storage.
@@ -400,26 +1144,276 @@ impl<S: Storage, M: Manifest<S::Unifiers>, C: Cache> Database<S, M, C> {
         &self.unifiers
     }
 
-    /// Helper function to process iterator results and get deserialized values
-    fn process_iter_result<T: DeserializeOwned>(
+    /// Serializes the key of an entry in the reserved subtable of `scope`.
+    fn reserved_key(
         &self,
-        result: Result<<StorageKU<S> as Unifier>::D, <S::Repo as Repository>::Error>,
-    ) -> Result<T, DatabaseError<S>> {
-        let key = result.map_err(DatabaseError::Storage)?;
+        scope: u8,
+        entry: &ReservedEntry,
+    ) -> Result<StorageKeyBuffer<S>, DatabaseError<S>> {
+        let mut key = StorageKeyBuffer::<S>::default();
+        reserved_wrap(scope, entry, &self.unifiers.key_unifier(), &mut key)
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        Ok(key)
+    }
 
-        let value = match self.storage.repository().get_entry(key.as_view()) {
-            Ok(Some(data)) => data,
-            Ok(None) => {
-                return Err(DatabaseError::Internal(
-                    crate::InternalDatabaseError::MissingIndexEntry,
-                ));
-            }
-            Err(e) => return Err(DatabaseError::Storage(e)),
+    /// Serializes the value of an entry in a reserved subtable.
+    fn reserved_value(
+        &self,
+        value: &impl Serialize,
+    ) -> Result<<StorageVU<S> as Unifier>::D, DatabaseError<S>> {
+        let mut buffer = <StorageVU<S> as Unifier>::D::default();
+        self.unifiers
+            .value_unifier()
+            .serialize(&mut buffer, value)
+            .map_err(TransactionError::from_value)?;
+        Ok(buffer)
+    }
+
+    /// Reads the value of an entry in a reserved subtable, if it's stored.
+    fn read_reserved<T: DeserializeOwned>(
+        &self,
+        key: &StorageKeyBuffer<S>,
+    ) -> Result<Option<T>, DatabaseError<S>> {
+        let Some(value) = self
+            .storage
+            .repository()
+            .get_entry(key.as_view())
+            .map_err(DatabaseError::Storage)?
+        else {
+            return Ok(None);
         };
+        self.unifiers
+            .value_unifier()
+            .deserialize(&value)
+            .map(Some)
+            .map_err(DatabaseError::ValueDeserialization)
+    }
+
+    /// Returns whether the values of `scope` are stored in [`Envelope`]s, as recorded by the stored fingerprint.
+    fn stored_enveloped(&self, scope: u8) -> Result<bool, DatabaseError<S>> {
+        let key = self.reserved_key(scope, &ReservedEntry::Schema)?;
+        let fingerprint: Option<String> = self.read_reserved(&key)?;
+        Ok(fingerprint.is_some_and(|fingerprint| enveloped(&fingerprint)))
+    }
+
+    /// Returns [`DatabaseError::PendingMigration`] if the records of `scope` have to be migrated before they're accessed.
+    fn check_migrated(&self, scope: u8) -> Result<(), DatabaseError<S>> {
+        match self.pending.iter().find(|(pending, _)| *pending == scope) {
+            Some(&(scope, version)) => Err(DatabaseError::PendingMigration { scope, version }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns true if no records are stored in the main subtable of `scope`.
+    fn scope_is_empty(&self, scope: u8) -> Result<bool, DatabaseError<S>> {
+        let (start, end) = scope_wrap(scope, &self.unifiers.key_unifier())
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        let mut keys = self
+            .storage
+            .repository()
+            .scan_range(start..end, Direction::Ascending)
+            .map_err(DatabaseError::Storage)?;
+        Ok(keys
+            .next()
+            .transpose()
+            .map_err(DatabaseError::Storage)?
+            .is_none())
+    }
+
+    /// Returns the version the records of `scope` are stored at, 1 for tables stored before versions were recorded.
+    fn stored_version(&self, scope: u8) -> Result<u32, DatabaseError<S>> {
+        let key = self.reserved_key(scope, &ReservedEntry::Version)?;
+        Ok(self.read_reserved(&key)?.unwrap_or(1))
+    }
+
+    /// Scans the keys within a raw storage range.
+    fn scan_keys<K: RecordKey>(
+        &self,
+        range: Range<StorageKeyBuffer<S>>,
+        direction: Direction,
+    ) -> Result<
+        impl Iterator<Item = DatabaseIteratorItem<K::Record, S>> + use<'_, K, S, M, C>,
+        DatabaseError<S>,
+    >
+    where
+        K::Record: DatabaseEntry<Key = K>,
+    {
+        let raw_iter = self
+            .storage
+            .repository()
+            .scan_range(range, direction)
+            .map_err(DatabaseError::Storage)?;
+
+        Ok(raw_iter.map(|elem| self.process_key_result(elem)))
+    }
+
+    /// Scans the records, together with their keys, within a raw storage range.
+    fn scan_records<R>(
+        &self,
+        range: Range<StorageKeyBuffer<S>>,
+        direction: Direction,
+    ) -> Result<
+        impl Iterator<Item = DatabaseRecordItem<R, S>> + use<'_, R, S, M, C>,
+        DatabaseError<S>,
+    >
+    where
+        R: DatabaseEntry,
+        R::Key: RecordKey<Record = R>,
+    {
+        self.check_migrated(R::SCOPE)?;
+        let raw_iter = self
+            .storage
+            .repository()
+            .scan_range_entries(range, direction)
+            .map_err(DatabaseError::Storage)?;
+
+        Ok(raw_iter.map(|elem| self.process_record_entry(elem)))
+    }
+
+    /// Serializes the bounds of a primary key range into a raw storage range.
+    fn key_range<R: DatabaseEntry>(
+        &self,
+        range: &impl RangeBounds<R::Key>,
+    ) -> Result<Range<StorageKeyBuffer<S>>, DatabaseError<S>> {
+        let prefix = self.main_prefix::<R>()?;
+        let bounds = empty_wrap::<R, StorageKU<S>>(&self.unifiers.key_unifier())
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        self.raw_range(&prefix, range, bounds)
+    }
+
+    /// Serializes the bounds of an index value range into a raw storage range.
+    fn index_range<I: Index>(
+        &self,
+        range: &impl RangeBounds<I>,
+    ) -> Result<Range<StorageKeyBuffer<S>>, DatabaseError<S>> {
+        let prefix = self.index_prefix::<I>()?;
+        let lower = StorageKeyBuffer::<S>::duplicate(prefix.as_view())
+            .map_err(|e| DatabaseError::from_buffer_overflow_or(BufferOverflowOr::overflow(e)))?;
+        let mut upper = lower.clone();
+        upper
+            .next()
+            .map_err(|e| DatabaseError::from_buffer_overflow_or(BufferOverflowOr::overflow(e)))?;
+        self.raw_range(&prefix, range, (lower, upper))
+    }
+
+    /// Serializes the prelude shared by all entries of an index.
+    fn index_prefix<I: Index>(&self) -> Result<StorageKeyBuffer<S>, DatabaseError<S>> {
+        let mut prefix = StorageKeyBuffer::<S>::default();
+        self.unifiers
+            .key_unifier()
+            .serialize(
+                &mut prefix,
+                &WrapPrelude::new::<I::Record>(Subtable::Index(I::INDEX)),
+            )
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        Ok(prefix)
+    }
+
+    /// Serializes the prelude shared by all main subtable keys of a record type.
+    fn main_prefix<R: DatabaseEntry>(&self) -> Result<StorageKeyBuffer<S>, DatabaseError<S>> {
+        let mut prefix = StorageKeyBuffer::<S>::default();
+        self.unifiers
+            .key_unifier()
+            .serialize(&mut prefix, &WrapPrelude::new::<R>(Subtable::Main))
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        Ok(prefix)
+    }
+
+    /// Serializes range bounds, each appended to `prefix`, into a raw storage range.
+    ///
+    /// Unbounded ends fall back to `lower` and `upper`, the boundaries of the whole subtable.
+    fn raw_range<T: Serialize>(
+        &self,
+        prefix: &StorageKeyBuffer<S>,
+        range: &impl RangeBounds<T>,
+        (lower, upper): (StorageKeyBuffer<S>, StorageKeyBuffer<S>),
+    ) -> Result<Range<StorageKeyBuffer<S>>, DatabaseError<S>> {
+        let start = match range.start_bound() {
+            Bound::Included(value) => self.bound_buffer(prefix, value, false)?,
+            Bound::Excluded(value) => self.bound_buffer(prefix, value, true)?,
+            Bound::Unbounded => lower,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(value) => self.bound_buffer(prefix, value, true)?,
+            Bound::Excluded(value) => self.bound_buffer(prefix, value, false)?,
+            Bound::Unbounded => upper,
+        };
+        Ok(start..end)
+    }
+
+    /// Serializes `value` after `prefix`, moving past all keys starting with it if `past` is set.
+    fn bound_buffer<T: Serialize>(
+        &self,
+        prefix: &StorageKeyBuffer<S>,
+        value: &T,
+        past: bool,
+    ) -> Result<StorageKeyBuffer<S>, DatabaseError<S>> {
+        let mut buffer = StorageKeyBuffer::<S>::duplicate(prefix.as_view())
+            .map_err(|e| DatabaseError::from_buffer_overflow_or(BufferOverflowOr::overflow(e)))?;
+        self.unifiers
+            .key_unifier()
+            .serialize(&mut buffer, value)
+            .map_err(DatabaseError::from_buffer_overflow_or)?;
+        if past {
+            buffer.next().map_err(|e| {
+                DatabaseError::from_buffer_overflow_or(BufferOverflowOr::overflow(e))
+            })?;
+        }
+        Ok(buffer)
+    }
+
+    /// Helper function to deserialize a scanned main subtable key
+    fn process_key_result<K: DeserializeOwned>(
+        &self,
+        result: Result<StorageKeyBuffer<S>, <S::Repo as Repository>::Error>,
+    ) -> Result<K, DatabaseError<S>> {
+        let key = result.map_err(DatabaseError::Storage)?;
+
+        self.unifiers
+            .key_unifier()
+            .deserialize_wrapped(&key)
+            .map_err(DatabaseError::KeyDeserialization)
+    }
+
+    /// Helper function to deserialize the primary key held by a scanned entry of an index
+    fn process_index_entry<R: DatabaseEntry>(
+        &self,
+        result: StorageEntry<S>,
+        discriminator: u8,
+    ) -> Result<R::Key, DatabaseError<S>> {
+        let (_, value) = result.map_err(DatabaseError::Storage)?;
+
+        R::index_owner(&value, discriminator, &self.unifiers.value_unifier())
+            .map_err(DatabaseError::ValueDeserialization)
+    }
+
+    /// Helper function to process index entries and get the deserialized values
+    fn process_iter_result<T: DeserializeOwned>(
+        &self,
+        result: StorageEntry<S>,
+    ) -> Result<T, DatabaseError<S>> {
+        let (_, value) = result.map_err(DatabaseError::Storage)?;
 
         self.unifiers
             .value_unifier()
             .deserialize(&value)
             .map_err(DatabaseError::ValueDeserialization)
     }
+
+    /// Helper function to deserialize a main subtable entry into its key and record
+    fn process_record_entry<R: DatabaseEntry>(
+        &self,
+        result: StorageEntry<S>,
+    ) -> Result<(R::Key, R), DatabaseError<S>> {
+        let (key, value) = result.map_err(DatabaseError::Storage)?;
+
+        let key = self
+            .unifiers
+            .key_unifier()
+            .deserialize_wrapped(&key)
+            .map_err(DatabaseError::KeyDeserialization)?;
+        let (record, _) = self.decode_record(&value, R::ENVELOPE, R::VERSION)?;
+
+        Ok((key, record))
+    }
 }
@@ -1,3 +1,5 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::{string::String, vec::Vec};
 use core::{
     error::Error,
     fmt::{self, Debug, Display},
@@ -66,6 +68,23 @@ pub enum DatabaseError<S: Storage> {
     ValueDeserialization(<StorageVU<S> as Unifier>::DeError),
     /// Errors that occur when trying to increment a key.
     FailedToIncrement,
+    /// An update changed the record's derived key, which would move it to a different entry.
+    DerivedKeyChanged,
+    /// A write gave a unique index value to a record while another record already owns it.
+    UniqueViolation,
+    /// The database was written by record types that differ from the ones of the manifest.
+    SchemaMismatch(SchemaDiff),
+    /// A stored value has a version that its record type can't be converted from.
+    UnsupportedVersion {
+        scope: u8,
+        version: u32,
+    },
+    /// The records of a scope are stored at an older version of their type, and can't be read or written
+    /// until they're converted by [`Database::migrate`](crate::Database::migrate).
+    PendingMigration {
+        scope: u8,
+        version: u32,
+    },
     /// Internal errors that should never occur during normal operation of the database.
     Internal(InternalDatabaseError),
 }
@@ -88,11 +107,80 @@ where
                 f.debug_tuple("ValueDeserialization").field(e).finish()
             }
             Self::FailedToIncrement => write!(f, "FailedToIncrement"),
+            Self::DerivedKeyChanged => write!(f, "DerivedKeyChanged"),
+            Self::UniqueViolation => write!(f, "UniqueViolation"),
+            Self::SchemaMismatch(diff) => f.debug_tuple("SchemaMismatch").field(diff).finish(),
+            Self::UnsupportedVersion { scope, version } => f
+                .debug_struct("UnsupportedVersion")
+                .field("scope", scope)
+                .field("version", version)
+                .finish(),
+            Self::PendingMigration { scope, version } => f
+                .debug_struct("PendingMigration")
+                .field("scope", scope)
+                .field("version", version)
+                .finish(),
             Self::Internal(e) => f.debug_tuple("Internal").field(e).finish(),
         }
     }
 }
 
+/// The differences between the schemas stored in a database and the record types of a manifest,
+/// returned in [`DatabaseError::SchemaMismatch`].
+///
+/// Displayed as a diff of the fingerprints of the changed tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDiff {
+    pub tables: Vec<TableChange>,
+}
+
+/// A table whose stored fingerprint differs from the [`FINGERPRINT`](crate::DatabaseEntry::FINGERPRINT) of its record type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableChange {
+    pub scope: u8,
+    /// Parts of the stored fingerprint that the current one lacks.
+    pub removed: Vec<String>,
+    /// Parts of the current fingerprint that the stored one lacks.
+    pub added: Vec<String>,
+}
+
+impl TableChange {
+    /// Compares the parts of two fingerprints, returning `None` if they're equal.
+    #[must_use]
+    pub fn between(scope: u8, stored: &str, current: &str) -> Option<Self> {
+        if stored == current {
+            return None;
+        }
+        let missing_from = |parts: &str, other: &str| {
+            parts
+                .split("; ")
+                .filter(|part| !other.split("; ").any(|known| known == *part))
+                .map(String::from)
+                .collect()
+        };
+        Some(TableChange {
+            scope,
+            removed: missing_from(stored, current),
+            added: missing_from(current, stored),
+        })
+    }
+}
+
+impl Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for table in &self.tables {
+            writeln!(f, "scope {}:", table.scope)?;
+            for part in &table.removed {
+                writeln!(f, "  - {part}")?;
+            }
+            for part in &table.added {
+                writeln!(f, "  + {part}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Internal errors that should never arise during normal operation of the database.
 ///
 /// These errors indicate a bug in the database implementation or database corruption.
@@ -157,6 +245,25 @@ impl<S: Storage> fmt::Display for DatabaseError<S> {
             Self::KeyDeserialization(ref e) => write!(f, "Key deserialization error: {e}"),
             Self::ValueDeserialization(ref e) => write!(f, "Value deserialization error: {e}"),
             Self::FailedToIncrement => write!(f, "Failed to increment key value"),
+            Self::DerivedKeyChanged => write!(f, "Update changed the record's derived key"),
+            Self::UniqueViolation => {
+                write!(f, "Unique index value is already owned by another record")
+            }
+            Self::SchemaMismatch(ref diff) => {
+                write!(f, "Stored schema differs from the manifest:\n{diff}")
+            }
+            Self::UnsupportedVersion { scope, version } => {
+                write!(
+                    f,
+                    "Values of scope {scope} can't be migrated from version {version}"
+                )
+            }
+            Self::PendingMigration { scope, version } => {
+                write!(
+                    f,
+                    "Records of scope {scope} are stored at version {version} and have to be migrated"
+                )
+            }
             Self::Internal(ref e) => write!(f, "Internal database error: {e}"),
         }
     }
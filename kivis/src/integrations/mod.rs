@@ -3,4 +3,5 @@ mod repository;
 pub use repository::*;
 
 mod unifier;
+pub use unifier::{ESCAPED_STR_CAPACITY, OrderedError, OrderedUnifier};
 mod unifier_data;
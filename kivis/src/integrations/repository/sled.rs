@@ -3,7 +3,8 @@ use std::error::Error;
 use std::fmt::{Debug, Display};
 
 use crate::{
-    ApplyError, BufferOverflowError, BufferOverflowOr, Repository, Storage, Unified, Unifier,
+    ApplyError, BufferOverflowError, BufferOverflowOr, Direction, OrderedUnifier, Repository,
+    Storage, Unified, Unifier,
 };
 
 /// Error type for [`SledStorage`] operations.
@@ -90,6 +91,25 @@ impl Storage for sled::Db {
     }
 }
 
+/// Sled storage with keys encoded by [`OrderedUnifier`], so that range queries follow the order of the keys.
+///
+/// A plain [`sled::Db`] stores its keys with [`PostcardUnifier`], which supports point lookups only.
+/// The two key formats are incompatible, so a database keeps the storage type it was created with.
+#[derive(Debug, Clone)]
+pub struct OrderedSled(pub sled::Db);
+
+impl Storage for OrderedSled {
+    type Repo = sled::Db;
+    type Unifiers = (OrderedUnifier, PostcardUnifier);
+    fn repository(&self) -> &Self::Repo {
+        &self.0
+    }
+
+    fn repository_mut(&mut self) -> &mut Self::Repo {
+        &mut self.0
+    }
+}
+
 impl Repository for sled::Db {
     type K = Vec<u8>;
     type V = Vec<u8>;
@@ -114,19 +134,39 @@ impl Repository for sled::Db {
         }
     }
 
+    fn contains_entry(&self, key: &[u8]) -> Result<bool, Self::Error> {
+        Ok(self.contains_key(key)?)
+    }
+
+    fn count_range(&self, range: Range<Self::K>) -> Result<usize, Self::Error> {
+        let mut count = 0;
+        for key in self.range(range).keys() {
+            key?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     fn scan_range(
         &self,
         range: Range<Self::K>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = Result<Self::K, Self::Error>>, Self::Error> {
-        // Sled uses forward iteration, but kivis expects reverse order
-        // Collect all keys in range and reverse them
-        let keys: Vec<_> = self
-            .range(range.start..range.end)
-            .filter_map(Result::ok)
-            .map(|(k, _)| k.to_vec())
-            .collect();
-
-        Ok(keys.into_iter().rev().map(Ok))
+        // Sled iterators are double-ended, so both directions are streamed
+        let iter = direction.apply(self.range(range));
+        Ok(iter.map(|entry| Ok(entry?.0.to_vec())))
+    }
+
+    fn scan_range_entries(
+        &self,
+        range: Range<Self::K>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = Result<(Self::K, Self::V), Self::Error>>, Self::Error> {
+        let iter = direction.apply(self.range(range));
+        Ok(iter.map(|entry| {
+            let (k, v) = entry?;
+            Ok((k.to_vec(), v.to_vec()))
+        }))
     }
 
     fn apply<U, E>(
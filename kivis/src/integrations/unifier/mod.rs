@@ -2,3 +2,6 @@
 
 #[cfg(any(feature = "std", feature = "alloc"))]
 mod bincode;
+mod ordered;
+
+pub use ordered::{ESCAPED_STR_CAPACITY, OrderedError, OrderedUnifier};
@@ -0,0 +1,818 @@
+//! A memcomparable `Unifier`, whose output sorts bytewise in the same order as the serialized values.
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Debug, Display};
+use core::marker::PhantomData;
+
+use serde::{
+    Deserializer, Serialize, Serializer,
+    de::{
+        self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess,
+        SeqAccess, VariantAccess, Visitor, value::SeqDeserializer,
+    },
+    ser,
+};
+
+use crate::{BufferOverflowOr, OrderPreserving, Unified, Unifier};
+
+/// Order-preserving [`Unifier`] for byte buffers such as `Vec<u8>` and `heapless::Vec<u8, N>`.
+///
+/// Comparing two encodings bytewise gives the same result as comparing the original values with
+/// [`Ord`], which is what range scans over keys and indexes rely on. Every type of the serde data
+/// model is encoded as follows:
+///
+/// - unsigned integers and `char` are written big-endian at their full width,
+/// - signed integers are written big-endian with the sign bit flipped,
+/// - floats follow the total order of [`f64::total_cmp`],
+/// - strings and byte strings escape each `0x00` as `0x00 0xFF` and end with `0x00 0x00`,
+/// - `bool` and `Option` are a `0`/`1` tag, followed by the value for `Some`,
+/// - tuples, structs and their fields are concatenated in declaration order,
+/// - enum variants start with their index as a big-endian `u32`,
+/// - sequences and maps prefix each element with `1` and end with `0`.
+///
+/// The encoding is not self-describing, so types that rely on `deserialize_any` can't be read back.
+/// It is the key unifier of [`MemoryStorage`](crate::MemoryStorage) and `OrderedSled`.
+///
+/// Reading doesn't allocate: strings and byte strings are borrowed from the input, except for
+/// strings holding a `0x00`, which are copied to a `String` or, without `alloc`, a buffer of
+/// [`ESCAPED_STR_CAPACITY`] bytes.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub struct OrderedUnifier<D = Vec<u8>>(PhantomData<fn() -> D>);
+
+/// Order-preserving [`Unifier`] for byte buffers such as `heapless::Vec<u8, N>`.
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+pub struct OrderedUnifier<D>(PhantomData<fn() -> D>);
+
+/// Longest string holding a `0x00` that [`OrderedUnifier`] reads without `alloc`.
+pub const ESCAPED_STR_CAPACITY: usize = 256;
+
+impl<D> OrderedUnifier<D> {
+    /// Creates a new `OrderedUnifier` writing into buffers of type `D`.
+    #[must_use]
+    pub const fn new() -> Self {
+        OrderedUnifier(PhantomData)
+    }
+}
+
+impl<D> Default for OrderedUnifier<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> Clone for OrderedUnifier<D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<D> Copy for OrderedUnifier<D> {}
+
+impl<D> Debug for OrderedUnifier<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OrderedUnifier")
+    }
+}
+
+impl<D> Unifier for OrderedUnifier<D>
+where
+    D: for<'a> Unified<View<'a> = &'a [u8]>,
+{
+    type D = D;
+    type SerError = OrderedError;
+    type DeError = OrderedError;
+
+    fn serialize(
+        &self,
+        buffer: &mut D,
+        data: &impl Serialize,
+    ) -> Result<(usize, usize), BufferOverflowOr<Self::SerError>> {
+        let start = buffer.len();
+        data.serialize(&mut OrderedSerializer { output: buffer })
+            .map_err(|e| match e {
+                OrderedError::BufferOverflow => BufferOverflowOr(None),
+                e => BufferOverflowOr(Some(e)),
+            })?;
+        Ok((start, buffer.len()))
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, data: &D) -> Result<T, Self::DeError> {
+        T::deserialize(&mut OrderedDeserializer {
+            input: data.as_view(),
+        })
+    }
+}
+
+impl<D> OrderPreserving for OrderedUnifier<D> where D: for<'a> Unified<View<'a> = &'a [u8]> {}
+
+/// Error type for [`OrderedUnifier`] operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderedError {
+    /// Error reported by a `Serialize` or `Deserialize` implementation, such as a value of the wrong type.
+    Custom,
+    /// The buffer could not hold the encoded value.
+    BufferOverflow,
+    /// The input ended in the middle of a value.
+    UnexpectedEnd,
+    /// A `bool`, `Option` or sequence tag other than `0` or `1`.
+    InvalidTag(u8),
+    /// A `0x00` in a string followed by a byte other than `0x00` or `0xFF`.
+    InvalidEscape(u8),
+    /// A `char` outside the range of Unicode scalar values.
+    InvalidChar(u32),
+    /// A string that is not valid UTF-8.
+    InvalidUtf8,
+    /// The type requires a self-describing format.
+    NotSelfDescribing,
+}
+
+impl Display for OrderedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Custom => {
+                f.write_str("Value rejected by its Serialize or Deserialize implementation")
+            }
+            Self::BufferOverflow => f.write_str("Buffer overflow"),
+            Self::UnexpectedEnd => f.write_str("Unexpected end of input"),
+            Self::InvalidTag(tag) => write!(f, "Invalid tag {tag}"),
+            Self::InvalidEscape(byte) => write!(f, "Invalid escape byte {byte:#04x}"),
+            Self::InvalidChar(value) => write!(f, "Invalid char {value:#x}"),
+            Self::InvalidUtf8 => f.write_str("Invalid UTF-8 in string"),
+            Self::NotSelfDescribing => f.write_str("Ordered encoding is not self-describing"),
+        }
+    }
+}
+
+impl core::error::Error for OrderedError {}
+
+impl ser::Error for OrderedError {
+    fn custom<T: Display>(_msg: T) -> Self {
+        Self::Custom
+    }
+}
+
+impl de::Error for OrderedError {
+    fn custom<T: Display>(_msg: T) -> Self {
+        Self::Custom
+    }
+}
+
+/// Maps a float's bits so that unsigned comparison matches [`f64::total_cmp`].
+fn ordered_bits_f64(value: f64) -> u64 {
+    let bits = value.to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | 1 << 63
+    }
+}
+
+fn f64_from_ordered_bits(bits: u64) -> f64 {
+    f64::from_bits(if bits >> 63 == 1 {
+        bits & !(1 << 63)
+    } else {
+        !bits
+    })
+}
+
+/// Maps a float's bits so that unsigned comparison matches [`f32::total_cmp`].
+fn ordered_bits_f32(value: f32) -> u32 {
+    let bits = value.to_bits();
+    if bits >> 31 == 1 {
+        !bits
+    } else {
+        bits | 1 << 31
+    }
+}
+
+fn f32_from_ordered_bits(bits: u32) -> f32 {
+    f32::from_bits(if bits >> 31 == 1 {
+        bits & !(1 << 31)
+    } else {
+        !bits
+    })
+}
+
+struct OrderedSerializer<'a, D> {
+    output: &'a mut D,
+}
+
+impl<D> OrderedSerializer<'_, D>
+where
+    D: for<'a> Unified<View<'a> = &'a [u8]>,
+{
+    fn write(&mut self, bytes: &[u8]) -> Result<(), OrderedError> {
+        self.output
+            .extend_from(bytes)
+            .map_err(|_| OrderedError::BufferOverflow)
+    }
+
+    fn write_escaped(&mut self, bytes: &[u8]) -> Result<(), OrderedError> {
+        for (i, chunk) in bytes.split(|byte| *byte == 0).enumerate() {
+            if i > 0 {
+                self.write(&[0x00, 0xFF])?;
+            }
+            self.write(chunk)?;
+        }
+        self.write(&[0x00, 0x00])
+    }
+}
+
+macro_rules! serialize_unsigned {
+    ($($method:ident: $ty:ty),*) => {
+        $(fn $method(self, v: $ty) -> Result<(), OrderedError> {
+            self.write(&v.to_be_bytes())
+        })*
+    };
+}
+
+macro_rules! serialize_signed {
+    ($($method:ident: $ty:ty),*) => {
+        $(fn $method(self, v: $ty) -> Result<(), OrderedError> {
+            self.write(&(v ^ <$ty>::MIN).to_be_bytes())
+        })*
+    };
+}
+
+impl<D> Serializer for &mut OrderedSerializer<'_, D>
+where
+    D: for<'a> Unified<View<'a> = &'a [u8]>,
+{
+    type Ok = ();
+    type Error = OrderedError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    serialize_unsigned!(
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128
+    );
+    serialize_signed!(
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128
+    );
+
+    fn serialize_bool(self, v: bool) -> Result<(), OrderedError> {
+        self.write(&[u8::from(v)])
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), OrderedError> {
+        self.write(&ordered_bits_f32(v).to_be_bytes())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), OrderedError> {
+        self.write(&ordered_bits_f64(v).to_be_bytes())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), OrderedError> {
+        self.serialize_u32(u32::from(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), OrderedError> {
+        self.write_escaped(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), OrderedError> {
+        self.write_escaped(v)
+    }
+
+    fn serialize_none(self) -> Result<(), OrderedError> {
+        self.write(&[0])
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), OrderedError> {
+        self.write(&[1])?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), OrderedError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), OrderedError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), OrderedError> {
+        self.serialize_u32(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), OrderedError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), OrderedError> {
+        self.serialize_u32(variant_index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, OrderedError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, OrderedError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self, OrderedError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, OrderedError> {
+        self.serialize_u32(variant_index)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, OrderedError> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, OrderedError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, OrderedError> {
+        self.serialize_u32(variant_index)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<D> ser::SerializeSeq for &mut OrderedSerializer<'_, D>
+where
+    D: for<'a> Unified<View<'a> = &'a [u8]>,
+{
+    type Ok = ();
+    type Error = OrderedError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OrderedError> {
+        self.write(&[1])?;
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), OrderedError> {
+        self.write(&[0])
+    }
+}
+
+impl<D> ser::SerializeMap for &mut OrderedSerializer<'_, D>
+where
+    D: for<'a> Unified<View<'a> = &'a [u8]>,
+{
+    type Ok = ();
+    type Error = OrderedError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), OrderedError> {
+        self.write(&[1])?;
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), OrderedError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), OrderedError> {
+        self.write(&[0])
+    }
+}
+
+macro_rules! serialize_concatenated {
+    ($($trait:ident::$method:ident($($name:ident: $ty:ty),*)),*) => {
+        $(impl<D> ser::$trait for &mut OrderedSerializer<'_, D>
+        where
+            D: for<'a> Unified<View<'a> = &'a [u8]>,
+        {
+            type Ok = ();
+            type Error = OrderedError;
+
+            fn $method<T: ?Sized + Serialize>(
+                &mut self,
+                $($name: $ty,)*
+                value: &T,
+            ) -> Result<(), OrderedError> {
+                value.serialize(&mut **self)
+            }
+
+            fn end(self) -> Result<(), OrderedError> {
+                Ok(())
+            }
+        })*
+    };
+}
+
+serialize_concatenated!(
+    SerializeTuple::serialize_element(),
+    SerializeTupleStruct::serialize_field(),
+    SerializeTupleVariant::serialize_field(),
+    SerializeStruct::serialize_field(_key: &'static str),
+    SerializeStructVariant::serialize_field(_key: &'static str)
+);
+
+struct OrderedDeserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> OrderedDeserializer<'de> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], OrderedError> {
+        let (head, rest) = self
+            .input
+            .split_first_chunk::<N>()
+            .ok_or(OrderedError::UnexpectedEnd)?;
+        self.input = rest;
+        Ok(*head)
+    }
+
+    fn tag(&mut self) -> Result<bool, OrderedError> {
+        match self.take::<1>()? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [tag] => Err(OrderedError::InvalidTag(tag)),
+        }
+    }
+
+    /// Takes an escaped string up to its terminator, returning it without unescaping.
+    fn take_escaped(&mut self) -> Result<Escaped<'de>, OrderedError> {
+        let mut end = 0;
+        loop {
+            end += self.input[end..]
+                .iter()
+                .position(|byte| *byte == 0)
+                .ok_or(OrderedError::UnexpectedEnd)?;
+            match self.input.get(end + 1) {
+                Some(0x00) => break,
+                Some(0xFF) => end += 2,
+                Some(byte) => return Err(OrderedError::InvalidEscape(*byte)),
+                None => return Err(OrderedError::UnexpectedEnd),
+            }
+        }
+        let escaped = Escaped(&self.input[..end]);
+        self.input = &self.input[end + 2..];
+        Ok(escaped)
+    }
+
+    fn take_u32(&mut self) -> Result<u32, OrderedError> {
+        Ok(u32::from_be_bytes(self.take()?))
+    }
+}
+
+macro_rules! deserialize_unsigned {
+    ($($method:ident => $visit:ident: $ty:ty),*) => {
+        $(fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+            visitor.$visit(<$ty>::from_be_bytes(self.take()?))
+        })*
+    };
+}
+
+macro_rules! deserialize_signed {
+    ($($method:ident => $visit:ident: $ty:ty),*) => {
+        $(fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+            visitor.$visit(<$ty>::from_be_bytes(self.take()?) ^ <$ty>::MIN)
+        })*
+    };
+}
+
+impl<'de> Deserializer<'de> for &mut OrderedDeserializer<'de> {
+    type Error = OrderedError;
+
+    deserialize_unsigned!(
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128
+    );
+    deserialize_signed!(
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128
+    );
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, OrderedError> {
+        Err(OrderedError::NotSelfDescribing)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        visitor.visit_bool(self.tag()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        visitor.visit_f32(f32_from_ordered_bits(self.take_u32()?))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        visitor.visit_f64(f64_from_ordered_bits(u64::from_be_bytes(self.take()?)))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        let value = self.take_u32()?;
+        visitor.visit_char(char::from_u32(value).ok_or(OrderedError::InvalidChar(value))?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        let escaped = self.take_escaped()?;
+        if let Some(bytes) = escaped.as_plain() {
+            return visitor.visit_borrowed_str(
+                core::str::from_utf8(bytes).map_err(|_| OrderedError::InvalidUtf8)?,
+            );
+        }
+        escaped.visit_unescaped_str(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        let escaped = self.take_escaped()?;
+        if let Some(bytes) = escaped.as_plain() {
+            return visitor.visit_borrowed_bytes(bytes);
+        }
+        let mut bytes = SeqDeserializer::new(escaped.unescaped());
+        let value = visitor.visit_seq(&mut bytes)?;
+        bytes.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        if self.tag()? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        visitor.visit_seq(Tagged(self))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        visitor.visit_seq(Counted(self, len))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        visitor.visit_seq(Counted(self, len))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        visitor.visit_map(Tagged(self))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        visitor.visit_seq(Counted(self, fields.len()))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OrderedError> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        Err(OrderedError::NotSelfDescribing)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// A string or byte string as stored, with each `0x00` escaped as `0x00 0xFF` and without its terminator.
+struct Escaped<'de>(&'de [u8]);
+
+impl<'de> Escaped<'de> {
+    /// Returns the bytes if they need no unescaping.
+    fn as_plain(&self) -> Option<&'de [u8]> {
+        (!self.0.contains(&0)).then_some(self.0)
+    }
+
+    /// Iterates over the unescaped bytes.
+    fn unescaped(&self) -> impl Iterator<Item = u8> + 'de {
+        let mut bytes = self.0.iter().copied();
+        core::iter::from_fn(move || {
+            let byte = bytes.next()?;
+            if byte == 0 {
+                // Skips the `0xFF` of the escape, checked by `take_escaped`
+                bytes.next();
+            }
+            Some(byte)
+        })
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_unescaped_str<V: Visitor<'de>>(&self, visitor: V) -> Result<V::Value, OrderedError> {
+        let string = String::from_utf8(self.unescaped().collect::<Vec<_>>())
+            .map_err(|_| OrderedError::InvalidUtf8)?;
+        visitor.visit_string(string)
+    }
+
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    fn visit_unescaped_str<V: Visitor<'de>>(&self, visitor: V) -> Result<V::Value, OrderedError> {
+        let mut buffer = [0; ESCAPED_STR_CAPACITY];
+        let mut len = 0;
+        for byte in self.unescaped() {
+            *buffer.get_mut(len).ok_or(OrderedError::BufferOverflow)? = byte;
+            len += 1;
+        }
+        visitor
+            .visit_str(core::str::from_utf8(&buffer[..len]).map_err(|_| OrderedError::InvalidUtf8)?)
+    }
+}
+
+/// Access to a fixed number of concatenated elements, as in tuples and structs.
+struct Counted<'a, 'de>(&'a mut OrderedDeserializer<'de>, usize);
+
+impl<'de> SeqAccess<'de> for Counted<'_, 'de> {
+    type Error = OrderedError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, OrderedError> {
+        if self.1 == 0 {
+            return Ok(None);
+        }
+        self.1 -= 1;
+        seed.deserialize(&mut *self.0).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.1)
+    }
+}
+
+/// Access to elements each preceded by a `1` tag and terminated by a `0`, as in sequences and maps.
+struct Tagged<'a, 'de>(&'a mut OrderedDeserializer<'de>);
+
+impl<'de> SeqAccess<'de> for Tagged<'_, 'de> {
+    type Error = OrderedError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, OrderedError> {
+        if self.0.tag()? {
+            seed.deserialize(&mut *self.0).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for Tagged<'_, 'de> {
+    type Error = OrderedError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, OrderedError> {
+        self.next_element_seed(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, OrderedError> {
+        seed.deserialize(&mut *self.0)
+    }
+}
+
+impl<'de> EnumAccess<'de> for &mut OrderedDeserializer<'de> {
+    type Error = OrderedError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), OrderedError> {
+        let index = self.take_u32()?;
+        let value = seed.deserialize(IntoDeserializer::<OrderedError>::into_deserializer(index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut OrderedDeserializer<'de> {
+    type Error = OrderedError;
+
+    fn unit_variant(self) -> Result<(), OrderedError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, OrderedError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        visitor.visit_seq(Counted(self, len))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OrderedError> {
+        visitor.visit_seq(Counted(self, fields.len()))
+    }
+}
@@ -18,7 +18,7 @@
 //! - `atomic` (default): Enable atomic transaction support (requires `alloc`)
 //! - `memory-storage` (default): Include in-memory storage implementation
 //! - `heapless`: Enable `UnifierData` implementation for `heapless::Vec<u8, N>`, allowing fixed-capacity
-//!   stack-allocated vectors for embedded environments
+//!   stack-allocated vectors for embedded environments, and `heapless::String<N>` keys in [`Lexicographic`]
 //!
 //! ## Quick Start
 //!
@@ -58,14 +58,15 @@ mod transaction;
 mod utils;
 mod wrap;
 
-pub use database::Database;
+pub use database::{Database, MIGRATION_BATCH};
 pub use kivis_derive::Record;
 pub use paste::paste;
 pub use traits::*;
 pub use utils::*;
 
 pub use crate::errors::{
-    BufferOverflowError, BufferOverflowOr, DatabaseError, InternalDatabaseError,
+    BufferOverflowError, BufferOverflowOr, DatabaseError, InternalDatabaseError, SchemaDiff,
+    TableChange,
 };
 
 pub use traits::ApplyError;
@@ -78,5 +79,7 @@ pub use transaction::DatabaseTransaction;
 #[doc(hidden)]
 pub use transaction::{PreBufferOps, RecordOps, build_record_ops};
 
+pub use integrations::{ESCAPED_STR_CAPACITY, OrderedError, OrderedUnifier};
+
 #[cfg(feature = "sled")]
-pub use integrations::{PostcardUnifier, SledStorageError};
+pub use integrations::{OrderedSled, PostcardUnifier, SledStorageError};
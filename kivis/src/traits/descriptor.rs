@@ -0,0 +1,87 @@
+use crate::DatabaseEntry;
+
+/// A field of a record, with its type as written in the definition.
+///
+/// Fields of tuple structs are named by their position, as `"0"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDescriptor {
+    /// The name of the field, or its position in a tuple struct.
+    pub name: &'static str,
+    /// The type of the field as written in the definition, including any path, as `"Vec<(u8, String)>"`.
+    pub type_name: &'static str,
+}
+
+/// How the keys of a record are assigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyDescriptor {
+    /// Keys are assigned by [`Database::put`](crate::Database::put), of the given type.
+    Autoincrement { type_name: &'static str },
+    /// Keys are made of the fields marked with `#[key]`.
+    Fields(&'static [FieldDescriptor]),
+    /// Keys are derived from the record as the types listed in `#[derived_key(...)]`.
+    Derived(&'static [&'static str]),
+    /// Keys of a record implementing [`DatabaseEntry`] by hand, which aren't described.
+    Opaque,
+}
+
+/// An index of a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexDescriptor {
+    /// The name of the index in Pascal case, its type is `{Record}{name}Index`.
+    pub name: &'static str,
+    /// The discriminator of the index, see [`Index::INDEX`](crate::Index::INDEX).
+    pub discriminator: u8,
+    /// The fields the index reads, empty for indexes computed from the whole record.
+    pub fields: &'static [FieldDescriptor],
+    /// Whether each value of the index is held by a single record, as declared with `#[index(unique)]`.
+    pub unique: bool,
+}
+
+/// The definition of a record type, available at runtime for tooling such as exporters and debug dumps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordDescriptor {
+    /// The name of the record type, empty for records described with [`RecordDescriptor::opaque`].
+    pub name: &'static str,
+    /// The scope of the table, as assigned by [`manifest!`](crate::manifest).
+    pub scope: u8,
+    /// The fields of the record, in the order they're defined and serialized.
+    pub fields: &'static [FieldDescriptor],
+    /// How the keys of the record are assigned.
+    pub key: KeyDescriptor,
+    /// The indexes of the record, ordered by discriminator.
+    pub indexes: &'static [IndexDescriptor],
+}
+
+impl RecordDescriptor {
+    /// Describes a record implementing [`DatabaseEntry`] by hand by its scope alone, without a name, fields or indexes.
+    ///
+    /// The other parts can be filled in with the struct update syntax:
+    ///
+    /// ```ignore
+    /// impl RecordSchema for User {
+    ///     const SCHEMA: RecordDescriptor = RecordDescriptor {
+    ///         name: "User",
+    ///         ..RecordDescriptor::opaque::<Self>()
+    ///     };
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn opaque<R: DatabaseEntry>() -> Self {
+        Self {
+            name: "",
+            scope: R::SCOPE,
+            fields: &[],
+            key: KeyDescriptor::Opaque,
+            indexes: &[],
+        }
+    }
+}
+
+/// Describes the definition of a record type.
+///
+/// Implemented by the derive macro, and listed for every table of a manifest by the `catalog` function generated
+/// by [`manifest!`](crate::manifest), which requires records implementing [`DatabaseEntry`] by hand to implement it too.
+/// For those, an empty `impl RecordSchema for User {}` describes the record with [`RecordDescriptor::opaque`].
+pub trait RecordSchema: DatabaseEntry {
+    const SCHEMA: RecordDescriptor = RecordDescriptor::opaque::<Self>();
+}
@@ -1,7 +1,13 @@
 /// Declarative macro to implement the Scope trait for multiple structs with a named manifest.
-/// Each struct gets its position in the array as its SCOPE value.
+/// Each struct gets its position in the array as its SCOPE value, unless a scope is listed after it
+/// (`User = 1`) or declared on the struct with `#[record(scope = N)]`.
 /// Also generates an empty manifest struct and assigns it as the Manifest type.
 ///
+/// Scopes identify the tables on disk, so databases that outlive a release should give every type
+/// an explicit scope, letting types be added, reordered and removed without remapping existing data.
+/// Scopes of removed types can be listed after `retired`, so they're never reused.
+/// Duplicate and retired scopes fail to compile.
+///
 /// # Example
 ///
 /// ```rust
@@ -37,6 +43,50 @@
 /// assert_eq!(Tag::SCOPE, 3);
 /// ```
 ///
+/// With explicit scopes, after the type that used scope 2 has been removed:
+///
+/// ```rust
+/// use kivis::{manifest, Scope, Record};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Record, Debug, Serialize, Deserialize)]
+/// struct User {
+///     id: u64,
+/// }
+///
+/// #[derive(Record, Debug, Serialize, Deserialize)]
+/// #[record(scope = 4)]
+/// struct Post {
+///     id: u64,
+/// }
+///
+/// manifest![App: Post, User = 1; retired = [2, 3]];
+///
+/// assert_eq!(User::SCOPE, 1);
+/// assert_eq!(Post::SCOPE, 4);
+/// assert_eq!(App::retired(), &[2, 3]);
+/// assert_eq!(App::catalog()[1].name, "User");
+/// ```
+///
+/// Two types can't share a scope:
+///
+/// ```compile_fail
+/// use kivis::{manifest, Record};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Record, Debug, Serialize, Deserialize)]
+/// struct User {
+///     id: u64,
+/// }
+///
+/// #[derive(Record, Debug, Serialize, Deserialize)]
+/// struct Post {
+///     id: u64,
+/// }
+///
+/// manifest![App: User = 1, Post = 1];
+/// ```
+///
 /// # Compilation Errors
 ///
 /// The macro requires a manifest name followed by a colon. Using the old syntax will fail:
@@ -72,7 +122,7 @@ macro_rules! manifest {
     };
 
     // Multiple items case with manifest name and cache type - generates cache struct, then delegates
-    ($manifest_name:ident + $cache_ty:ident: $($ty:ty),+ $(,)?) => {
+    ($manifest_name:ident + $cache_ty:ident: $($ty:ty $(= $scope:expr)?),+ $(,)? $(; retired = [$($retired:expr),* $(,)?])?) => {
         $crate::paste! {
             #[derive(Default)]
             pub struct [<$manifest_name Cache>]
@@ -100,11 +150,11 @@ macro_rules! manifest {
                 type Manifest = $manifest_name;
             }
         }
-        $crate::manifest!($manifest_name: $($ty),+);
+        $crate::manifest!($manifest_name: $($ty $(= $scope)?),+ $(; retired = [$($retired),*])?);
     };
 
     // Multiple items case with manifest name - generate implementations with incrementing indices
-    ($manifest_name:ident: $($ty:ty),+ $(,)?) => {
+    ($manifest_name:ident: $($ty:ty $(= $scope:expr)?),+ $(,)? $(; retired = [$($retired:expr),* $(,)?])?) => {
                 $crate::paste! {
                     #[derive(Default)]
                     pub struct $manifest_name {
@@ -115,23 +165,43 @@ macro_rules! manifest {
 
                     /// An enum holding a reference to any record type in this manifest.
                     /// All variants are pointer-sized (`&'a T`), so cloning is cheap.
+                    /// The last field holds the version being replaced, if any.
                     #[derive(Clone, Copy)]
                     pub enum [<$manifest_name Record>]<'a> {
                         $(
-                            [<$ty>](&'a <$ty as $crate::DatabaseEntry>::Key, &'a $ty),
+                            [<$ty>](&'a <$ty as $crate::DatabaseEntry>::Key, &'a $ty, ::core::option::Option<&'a $ty>),
                         )*
                     }
 
                     $(
                         impl<'a> ::core::convert::From<&'a (<$ty as $crate::DatabaseEntry>::Key, $ty)> for [<$manifest_name Record>]<'a> {
                             fn from(pair: &'a (<$ty as $crate::DatabaseEntry>::Key, $ty)) -> Self {
-                                [<$manifest_name Record>]::[<$ty>](&pair.0, &pair.1)
+                                [<$manifest_name Record>]::[<$ty>](&pair.0, &pair.1, ::core::option::Option::None)
+                            }
+                        }
+
+                        impl<'a> ::core::convert::From<&'a (<$ty as $crate::DatabaseEntry>::Key, $ty, $ty)> for [<$manifest_name Record>]<'a> {
+                            fn from(triple: &'a (<$ty as $crate::DatabaseEntry>::Key, $ty, $ty)) -> Self {
+                                [<$manifest_name Record>]::[<$ty>](&triple.0, &triple.1, ::core::option::Option::Some(&triple.2))
                             }
                         }
                     )*
                 }
 
-        $crate::scope_impl_with_index!($manifest_name, 0; $($ty),+);
+        $crate::scope_impl_with_index!($manifest_name, 0; $($ty $(= $scope)?),+);
+
+        const _: () = match $crate::scope_conflict(
+            &$crate::generate_member_scopes!(0; $($ty),+),
+            &[$($($retired),*)?],
+        ) {
+            ::core::option::Option::Some($crate::ScopeConflict::Duplicate) => ::core::panic!(::core::concat!(
+                "two record types of `", ::core::stringify!($manifest_name), "` have the same scope"
+            )),
+            ::core::option::Option::Some($crate::ScopeConflict::Retired) => ::core::panic!(::core::concat!(
+                "a record type of `", ::core::stringify!($manifest_name), "` has a retired scope"
+            )),
+            ::core::option::Option::None => (),
+        };
 
         $crate::paste! {
         impl $manifest_name {
@@ -139,6 +209,16 @@ macro_rules! manifest {
             pub fn members() -> &'static [u8] {
                 &$crate::generate_member_scopes!(0; $($ty),+)
             }
+
+            /// Returns the scopes of removed record types, which are never reused.
+            pub fn retired() -> &'static [u8] {
+                &[$($($retired),*)?]
+            }
+
+            /// Returns the descriptors of all record types in this manifest, in the order they're listed.
+            pub fn catalog() -> &'static [$crate::RecordDescriptor] {
+                &[$(<$ty as $crate::RecordSchema>::SCHEMA),*]
+            }
         }
 
         /// A concrete iterator over [`BatchOp`]s for any record type in this manifest.
@@ -182,12 +262,31 @@ macro_rules! manifest {
             where
                 Self: $crate::Manifest<S::Unifiers>,
             {
+                db.check_schema(&[
+                    $((
+                        <$ty as $crate::Scope>::SCOPE,
+                        <$ty as $crate::DatabaseEntry>::VERSION,
+                        <$ty as $crate::DatabaseEntry>::INDEX_COUNT_HINT,
+                        <$ty as $crate::DatabaseEntry>::FINGERPRINT,
+                    )),*
+                ])?;
                 $(
-                    self.[<last_ $ty:snake>] = ::core::option::Option::Some(db.last_id()?);
+                    self.[<last_ $ty:snake>] = ::core::option::Option::Some(db.load_sequence()?);
                 )*
                 ::core::result::Result::Ok(())
             }
 
+            fn migrate<S: $crate::Storage<Unifiers = __U>, C: $crate::Cache>(db: &mut $crate::Database<S, Self, C>) -> ::core::result::Result<usize, $crate::DatabaseError<S>>
+            where
+                __U: 'static,
+            {
+                let mut migrated = 0;
+                $(
+                    migrated += db.migrate_records::<$ty>()?;
+                )*
+                ::core::result::Result::Ok(migrated)
+            }
+
             fn iter_ops<'a>(
                 op: $crate::PreBufferOps,
                 record: Self::Record<'a>,
@@ -198,9 +297,10 @@ macro_rules! manifest {
             {
                 match record {
                     $(
-                        [<$manifest_name Record>]::[<$ty>](key, val) => {
+                        [<$manifest_name Record>]::[<$ty>](key, val, previous) => {
                             [<$manifest_name Ops>]::[<$ty>](
                                 $crate::build_record_ops::<$ty, __U>(op, val, key, unifiers)
+                                    .with_previous(previous)
                             )
                         }
                     )*
@@ -239,10 +339,10 @@ macro_rules! scope_impl_with_index {
     // Base case: no more types
     ($manifest_name:ident, $index:expr;) => {};
 
-    // Single type remaining
-    ($manifest_name:ident, $index:expr; $ty:ty) => {
+    // Implement for the first type and recurse
+    ($manifest_name:ident, $index:expr; $ty:ty $(= $scope:expr)? $(, $rest:ty $(= $rest_scope:expr)?)*) => {
         impl $crate::Scope for $ty {
-            const SCOPE: u8 = $index;
+            const SCOPE: u8 = $crate::resolve_scope!($ty, $index $(, $scope)?);
             type Manifest = $manifest_name;
         }
         impl $crate::Manifests<$ty> for $manifest_name {
@@ -252,21 +352,31 @@ macro_rules! scope_impl_with_index {
                 }
             }
         }
+        $crate::scope_impl_with_index!($manifest_name, $index + 1; $($rest $(= $rest_scope)?),*);
     };
+}
 
-    // Multiple types remaining - implement for first and recurse
-    ($manifest_name:ident, $index:expr; $ty:ty, $($rest:ty),+) => {
-        impl $crate::Scope for $ty {
-            const SCOPE: u8 = $index;
-            type Manifest = $manifest_name;
+/// Helper macro resolving the scope of a type from the one listed in `manifest!`,
+/// the one declared with `#[record(scope = N)]` and its position, in that order
+#[macro_export]
+macro_rules! resolve_scope {
+    ($ty:ty, $position:expr) => {
+        match <$ty as $crate::DatabaseEntry>::DECLARED_SCOPE {
+            ::core::option::Option::Some(declared) => declared,
+            ::core::option::Option::None => $position,
         }
-        impl $crate::Manifests<$ty> for $manifest_name {
-            fn last(&mut self) -> &mut ::core::option::Option<<$ty as $crate::DatabaseEntry>::Key> {
-                $crate::paste! {
-                    &mut self.[<last_ $ty:snake>]
-                }
+    };
+
+    ($ty:ty, $position:expr, $listed:expr) => {
+        match <$ty as $crate::DatabaseEntry>::DECLARED_SCOPE {
+            ::core::option::Option::Some(declared) if declared != $listed => {
+                ::core::panic!(::core::concat!(
+                    "the scope of `",
+                    ::core::stringify!($ty),
+                    "` listed in `manifest!` differs from its `#[record(scope)]`"
+                ))
             }
+            _ => $listed,
         }
-        $crate::scope_impl_with_index!($manifest_name, $index + 1; $($rest),+);
     };
 }
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::Unifier;
+
+/// The version of a definition of a record.
+///
+/// Implemented by the derive macro for records, with the version set by `#[record(version = N)]` or 1,
+/// and by hand for the definitions of their previous versions, used as [`Migrate::From`].
+pub trait Versioned {
+    const VERSION: u32;
+}
+
+/// A conversion of a record from the definition of its previous version.
+///
+/// Records declared with `#[record(version = N)]` above 1 must implement it. Values stored at older versions
+/// are read as [`Self::From`], itself migrated from its own previous definition if needed, and converted
+/// with [`Self::migrate`]. The oldest definition ends the chain with `From = Self`, returning the value unchanged.
+///
+/// Stored values are converted by [`Database::migrate`](crate::Database::migrate).
+pub trait Migrate: Versioned + DeserializeOwned {
+    /// The definition of the previous version.
+    type From: Migrate;
+
+    /// Converts a value of the previous version.
+    fn migrate(previous: Self::From) -> Self;
+}
+
+/// A stored value together with the version of the record that wrote it.
+///
+/// Values of records declared with `#[record(envelope)]` are stored in envelopes, so that values written
+/// by older versions can be converted when they're read, without migrating the whole table first.
+#[derive(Serialize, Deserialize)]
+pub struct Envelope<T> {
+    pub version: u32,
+    pub payload: T,
+}
+
+/// The version leading an [`Envelope`], read before its payload to find the definition the payload is read as.
+///
+/// Named like the envelope, so that formats storing structs as maps or with their names read it the same way.
+#[derive(Deserialize)]
+#[serde(rename = "Envelope")]
+pub(crate) struct EnvelopeHeader {
+    pub version: u32,
+}
+
+/// Reads a value stored at `version` as `T`, converting it through the chain of its previous definitions.
+///
+/// The value is read from an [`Envelope`] if `enveloped` is set.
+/// Returns `None` if no definition in the chain has the version.
+/// # Errors
+///
+/// Returns an error if the value can't be deserialized as the definition of its version.
+pub fn read_version<T: Migrate, U: Unifier>(
+    unifier: &U,
+    version: u32,
+    data: &U::D,
+    enveloped: bool,
+) -> Result<Option<T>, U::DeError> {
+    if version == T::VERSION {
+        if enveloped {
+            let envelope: Envelope<T> = unifier.deserialize(data)?;
+            return Ok(Some(envelope.payload));
+        }
+        return unifier.deserialize(data).map(Some);
+    }
+    if version > T::VERSION || T::From::VERSION >= T::VERSION {
+        return Ok(None);
+    }
+    Ok(read_version::<T::From, U>(unifier, version, data, enveloped)?.map(T::migrate))
+}
@@ -1,12 +1,16 @@
 mod cache;
+mod descriptor;
 mod incrementable_types;
 mod macros;
+mod migration;
 mod repository;
 mod schema;
 mod storage;
 mod unifier;
 
 pub use cache::*;
+pub use descriptor::*;
+pub use migration::*;
 pub use repository::*;
 pub use schema::*;
 pub use storage::*;
@@ -3,6 +3,46 @@ use core::{error::Error, fmt::Debug};
 
 use crate::{ApplyError, BatchOp, BufferOverflowError, Unified, Unifier, UnifierPair};
 
+/// The order in which a range scan yields keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the smallest key to the largest.
+    Ascending,
+    /// From the largest key to the smallest.
+    Descending,
+}
+
+impl Direction {
+    /// Walks a double-ended iterator in this direction, without buffering it.
+    pub fn apply<I: DoubleEndedIterator>(self, iter: I) -> Directed<I> {
+        Directed {
+            iter,
+            direction: self,
+        }
+    }
+}
+
+/// An iterator adapter yielding items from either end, created by [`Direction::apply`].
+pub struct Directed<I> {
+    iter: I,
+    direction: Direction,
+}
+
+impl<I: DoubleEndedIterator> Iterator for Directed<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.direction {
+            Direction::Ascending => self.iter.next(),
+            Direction::Descending => self.iter.next_back(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
 /// A trait defining a repository backend decoupled from serialization.
 ///
 /// The repository is responsible for storing and retrieving key-value pairs
@@ -48,7 +88,34 @@ pub trait Repository {
         &mut self,
         key: <Self::K as Unified>::View<'_>,
     ) -> Result<Option<Self::V>, Self::Error>;
-    /// Iterate over the keys in the repository that are in range.
+    /// Check whether a value is stored under the given key.
+    ///
+    /// The default implementation reads the value with [`Self::get_entry`] and discards it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage fails while looking up the key.
+    fn contains_entry(&self, key: <Self::K as Unified>::View<'_>) -> Result<bool, Self::Error> {
+        Ok(self.get_entry(key)?.is_some())
+    }
+
+    /// Count the keys in the repository that are in range.
+    ///
+    /// The default implementation walks [`Self::scan_range`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage fails during iteration.
+    fn count_range(&self, range: Range<Self::K>) -> Result<usize, Self::Error> {
+        let mut count = 0;
+        for key in self.scan_range(range, Direction::Ascending)? {
+            key?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Iterate over the keys in the repository that are in range, in the given direction.
     ///
     /// # Errors
     ///
@@ -56,8 +123,35 @@ pub trait Repository {
     fn scan_range(
         &self,
         range: Range<Self::K>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = IterationItem<Self::K, Self::Error>>, Self::Error>;
 
+    /// Iterate over the key-value pairs in the repository that are in range, in the given direction.
+    ///
+    /// The default implementation looks up every key yielded by [`Self::scan_range`] with
+    /// [`Self::get_entry`]. Backends that can read values while scanning should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage fails during iteration.
+    fn scan_range_entries(
+        &self,
+        range: Range<Self::K>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = IterationEntry<Self>>, Self::Error> {
+        let keys = self.scan_range(range, direction)?;
+        Ok(keys.filter_map(move |key| {
+            let key = match key {
+                Ok(key) => key,
+                Err(e) => return Some(Err(e)),
+            };
+            // Entries removed since the key was scanned are skipped.
+            self.get_entry(key.as_view())
+                .transpose()
+                .map(|value| value.map(|value| (key, value)))
+        }))
+    }
+
     /// Execute mixed insert and delete operations from a fallible iterator.
     ///
     /// Iterator errors are converted into `Self::Error` via [`From`]. Storage errors
@@ -95,3 +189,5 @@ pub trait Repository {
 }
 
 type IterationItem<K, E> = Result<K, E>;
+type IterationEntry<R> =
+    Result<(<R as Repository>::K, <R as Repository>::V), <R as Repository>::Error>;
@@ -33,6 +33,12 @@ impl<K: RecordKey> AsKey for K {
     }
 }
 
+/// A leading part of a composite key, serialized the same way as the first fields of the key.
+///
+/// Used by [`Database::iter_keys_with_prefix`](crate::Database::iter_keys_with_prefix) to scan all keys starting with it.
+/// The derive macro implements it for tuples of every proper leading subset of the key fields.
+pub trait KeyPrefix<K: RecordKey>: Serialize {}
+
 /// A trait defining how a key can be extracted from a record.
 /// This might be one of the fields, a composite key, a hash, random uuid or any other type of derivation.
 /// It shouldn't be implemented for auto-incrementing keys.
@@ -56,6 +62,28 @@ pub trait Index: Serialize + DeserializeOwned + Debug {
     const INDEX: u8;
 }
 
+/// An index mapping each value to at most one record, declared with `#[index(unique)]`.
+///
+/// Its entries are stored without the primary key suffix, writes that would give a value a second owner
+/// fail with [`DatabaseError::UniqueViolation`], and records can be looked up with
+/// [`Database::get_by_unique`](crate::Database::get_by_unique).
+pub trait UniqueIndex: Index {}
+
+/// An index whose entries store a projection of the record after its primary key, declared with `#[index(include(...))]`.
+///
+/// The projections can be read with [`Database::iter_projection_by_index`](crate::Database::iter_projection_by_index)
+/// without loading the records.
+pub trait CoveringIndex: Index {
+    /// The fields of the record stored in each entry.
+    type Projection: Serialize + DeserializeOwned;
+}
+
+/// A leading part of an index spanning several fields, serialized the same way as the first fields of the index.
+///
+/// Used by [`Database::iter_by_index_with_prefix`](crate::Database::iter_by_index_with_prefix) to scan all entries starting with it.
+/// The derive macro implements it for tuples of every proper leading subset of the indexed fields.
+pub trait IndexPrefix<I: Index>: Serialize {}
+
 /// A trait describing how a key can be auto-incremented, defined for numeric types.
 pub trait Incrementable: Default + Sized {
     /// The first and last valid values of the type.
@@ -69,6 +97,22 @@ pub trait DatabaseEntry: Scope + Serialize + DeserializeOwned + Debug {
     /// The primary key type for this database entry.
     type Key: RecordKey;
     const INDEX_COUNT_HINT: u8 = 0;
+    /// Discriminators of the indexes that are unique, see [`UniqueIndex`].
+    const UNIQUE_INDEXES: &'static [u8] = &[];
+    /// Discriminators of the indexes that are covering, see [`CoveringIndex`].
+    const COVERING_INDEXES: &'static [u8] = &[];
+    /// Description of the stored layout of the record, the types of its fields, its key and indexes, in parts separated by `; `.
+    /// Field and index names aren't part of it, so renaming them keeps the stored records readable.
+    ///
+    /// Generated by the derive macro and checked against the database when it is opened,
+    /// see [`Database::check_schema`](crate::Database::check_schema).
+    const FINGERPRINT: &'static str = "";
+    /// Version of the record, set with `#[record(version = N)]`, see [`Migrate`](crate::Migrate).
+    const VERSION: u32 = 1;
+    /// Whether values are stored in an [`Envelope`](crate::Envelope) with their version, set with `#[record(envelope)]`.
+    const ENVELOPE: bool = false;
+    /// Scope declared with `#[record(scope = N)]`, assigned by [`manifest!`](crate::manifest) instead of the position of the type.
+    const DECLARED_SCOPE: Option<u8> = None;
 
     /// Serializes a specific index into the provided buffer.
     /// # Errors
@@ -81,6 +125,101 @@ pub trait DatabaseEntry: Scope + Serialize + DeserializeOwned + Debug {
     ) -> Result<(), BufferOverflowOr<KU::SerError>> {
         Ok(())
     }
+
+    /// Returns how many entries the record holds in the index with the given discriminator.
+    ///
+    /// Single-valued indexes hold one entry, multi-valued indexes declared with `#[index(each)]` hold one per element.
+    fn index_entry_count(&self, _discriminator: u8) -> usize {
+        1
+    }
+
+    /// Serializes the value of the `element`-th entry of an index into the provided buffer.
+    ///
+    /// Defaults to [`Self::index_key`], the only entry of single-valued indexes.
+    /// # Errors
+    /// Returns an error if serializing the index fails.
+    fn index_entry<KU: Unifier>(
+        &self,
+        buffer: &mut KU::D,
+        discriminator: u8,
+        _element: usize,
+        serializer: &KU,
+    ) -> Result<(), BufferOverflowOr<KU::SerError>> {
+        self.index_key(buffer, discriminator, serializer)
+    }
+
+    /// Serializes every entry of an index, each appended to a copy of `prefix`, into `entries`.
+    ///
+    /// Defaults to [`Self::index_entry`] for each of the [`Self::index_entry_count`] entries,
+    /// multi-valued indexes visit their collection once instead.
+    /// # Errors
+    /// Returns an error if serializing the index fails.
+    fn index_entries<KU: Unifier>(
+        &self,
+        prefix: &KU::D,
+        discriminator: u8,
+        serializer: &KU,
+        entries: &mut impl Extend<KU::D>,
+    ) -> Result<(), BufferOverflowOr<KU::SerError>> {
+        for element in 0..self.index_entry_count(discriminator) {
+            let mut entry = prefix.clone();
+            self.index_entry(&mut entry, discriminator, element, serializer)?;
+            entries.extend(Some(entry));
+        }
+        Ok(())
+    }
+
+    /// Serializes the value stored in the entries of an index into the provided buffer.
+    ///
+    /// Defaults to the primary key, covering indexes store it together with the projection of the record.
+    /// # Errors
+    /// Returns an error if serializing the value fails.
+    fn index_value<VU: Unifier>(
+        &self,
+        buffer: &mut VU::D,
+        _discriminator: u8,
+        key: &Self::Key,
+        serializer: &VU,
+    ) -> Result<(), BufferOverflowOr<VU::SerError>> {
+        serializer.serialize(buffer, key)?;
+        Ok(())
+    }
+
+    /// Deserializes the primary key held by an entry of the index with the given discriminator.
+    ///
+    /// Defaults to the primary key alone, covering indexes read it together with the projection stored after it.
+    /// # Errors
+    /// Returns an error if deserializing the value fails.
+    fn index_owner<VU: Unifier>(
+        data: &VU::D,
+        _discriminator: u8,
+        unifier: &VU,
+    ) -> Result<Self::Key, VU::DeError> {
+        unifier.deserialize(data)
+    }
+
+    /// Reads a value stored at an older `version` of the record, converting it to the current one.
+    ///
+    /// The value is read from an [`Envelope`](crate::Envelope) if `enveloped` is set.
+    /// Returns `None` if the version can't be converted, as for records without migrations.
+    /// The derive macro delegates to [`read_version`](crate::read_version) for records above version 1.
+    /// # Errors
+    /// Returns an error if the value can't be deserialized as the definition of its version.
+    fn upgrade<VU: Unifier>(
+        _version: u32,
+        _data: &VU::D,
+        _unifier: &VU,
+        _enveloped: bool,
+    ) -> Result<Option<Self>, VU::DeError> {
+        Ok(None)
+    }
+
+    /// Derives the primary key from the record's contents.
+    ///
+    /// Returns `None` for records with auto-incrementing keys, which are not derived from the record.
+    fn derived_key(&self) -> Option<Self::Key> {
+        None
+    }
 }
 
 pub trait Manifests<T: Scope + DatabaseEntry> {
@@ -99,6 +238,7 @@ pub trait Manifest<U: UnifierPair>: Default + 'static {
 
     fn members() -> &'static [u8];
 
+    /// Loads the state kept by the manifest, such as the autoincrement sequences, from the database.
     /// # Errors
     ///
     /// Returns a [`DatabaseError`] if loading manifests requires access to the
@@ -110,6 +250,23 @@ pub trait Manifest<U: UnifierPair>: Default + 'static {
     where
         Self: Sized + Manifest<S::Unifiers>;
 
+    /// Migrates the records of every type whose stored version is older than its current one,
+    /// returning how many were rewritten, see [`Database::migrate`].
+    ///
+    /// Manifests generated by [`manifest!`](crate::manifest) migrate all their members, others nothing by default.
+    /// # Errors
+    ///
+    /// Returns a [`DatabaseError`] if reading, converting or writing the records fails.
+    fn migrate<S: Storage<Unifiers = U>, C: Cache>(
+        _db: &mut Database<S, Self, C>,
+    ) -> Result<usize, DatabaseError<S>>
+    where
+        Self: Sized,
+        U: 'static,
+    {
+        Ok(0)
+    }
+
     /// Converts a record operation into an iterator of [`BatchOp`]s.
     ///
     /// Implementations should delegate to [`build_record_ops`](crate::build_record_ops) for each
@@ -125,3 +282,37 @@ pub trait Scope {
     const SCOPE: u8;
     type Manifest;
 }
+
+/// A conflict between the scopes of a manifest, rejected at compile time by [`manifest!`](crate::manifest).
+#[doc(hidden)]
+pub enum ScopeConflict {
+    /// Two record types have the same scope.
+    Duplicate,
+    /// A record type has a retired scope.
+    Retired,
+}
+
+/// Finds a conflict between the scopes of the `members` of a manifest and its `retired` scopes.
+#[doc(hidden)]
+#[must_use]
+pub const fn scope_conflict(members: &[u8], retired: &[u8]) -> Option<ScopeConflict> {
+    let mut i = 0;
+    while i < members.len() {
+        let mut j = 0;
+        while j < retired.len() {
+            if members[i] == retired[j] {
+                return Some(ScopeConflict::Retired);
+            }
+            j += 1;
+        }
+        let mut j = i + 1;
+        while j < members.len() {
+            if members[i] == members[j] {
+                return Some(ScopeConflict::Duplicate);
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    None
+}
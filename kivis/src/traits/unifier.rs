@@ -13,7 +13,12 @@ struct Wrapped<R> {
     key: R,
 }
 
-pub trait Unified: Default + Clone {
+/// A buffer holding serialized keys or values.
+///
+/// Buffers are ordered like the keys of the storage, byte by byte for binary buffers.
+/// Transactions sort and compare the buffers of index entries and unique values when committing,
+/// so custom buffers have to implement [`Ord`], comparing only the bytes written to them.
+pub trait Unified: Default + Clone + Ord {
     /// The borrowed view type for this buffer (e.g., &[u8] for Vec<u8>, &str for String)
     type View<'a>;
 
@@ -103,6 +108,18 @@ pub trait Unifier: Copy {
     }
 }
 
+/// Marker for key unifiers whose encodings sort bytewise in the same order as the values sort by [`Ord`].
+///
+/// Range queries, such as [`Database::iter_keys`](crate::Database::iter_keys) and
+/// [`Database::iter_by_index`](crate::Database::iter_by_index), compute their bounds and result order on
+/// the encoded bytes, so they require the storage's key unifier to implement this trait.
+/// Implementations can be tested with [`check_order_preserving`](crate::check_order_preserving).
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not preserve the order of the values it encodes",
+    note = "range queries compare encoded keys, use an order-preserving key unifier such as `OrderedUnifier`"
+)]
+pub trait OrderPreserving: Unifier {}
+
 /// A pair of [`Unifier`] types for key and value serialization.
 ///
 /// Implemented automatically for any `(KU, VU)` tuple where both are [`Unifier`].
@@ -1,7 +1,9 @@
-use std::cmp::Ordering;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 use crate::{
-    BatchOp, BufferOverflowOr, DatabaseEntry, RecordKey, Unified, Unifier, UnifierPair,
+    BatchOp, BufferOverflowOr, DatabaseEntry, Envelope, RecordKey, Unified, Unifier, UnifierPair,
     transaction::buffer::PreBufferOps,
     wrap::{Subtable, WrapPrelude},
 };
@@ -10,15 +12,21 @@ use super::errors::TransactionError;
 
 /// Concrete iterator of [`BatchOp`]s for a single record write or delete.
 ///
-/// Yields index entries first (one per `INDEX_COUNT_HINT`), then the main record entry.
+/// Yields index entries first, for each of the `INDEX_COUNT_HINT` indexes, then the main record entry.
+/// When the record replaces a previous version, the entries of both versions are serialized once per index
+/// and compared, entries that did not change are skipped and stale ones are deleted before the new ones are written.
 pub struct RecordOps<'r, R: DatabaseEntry, U: UnifierPair> {
     record: &'r R,
     key: &'r R::Key,
+    /// Version of the record currently stored under `key`, if it is being replaced.
+    previous: Option<&'r R>,
     key_unifier: U::KeyUnifier,
     value_unifier: U::ValueUnifier,
     op: PreBufferOps,
     /// Counts through `0..INDEX_COUNT_HINT` (index phase) then `INDEX_COUNT_HINT` (main), then done.
     discriminator: u8,
+    /// Operations on the entries of the last visited index not yielded yet, in reverse order.
+    pending: Vec<BatchOp<U>>,
     /// Serialized primary key, computed once and reused across index entries.
     cached_key: Option<<U::KeyUnifier as Unifier>::D>,
     /// Serialized primary key as a value (write path only), computed once and reused.
@@ -34,22 +42,30 @@ where
     type Item = Result<BatchOp<U>, TransactionError<U>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.discriminator.cmp(&R::INDEX_COUNT_HINT) {
-            Ordering::Less => {
-                let d = self.discriminator;
-                self.discriminator += 1;
-                Some(self.index_op(d))
+        loop {
+            if let Some(op) = self.pending.pop() {
+                return Some(Ok(op));
             }
-            Ordering::Equal => {
-                self.discriminator += 1;
-                Some(self.main_op())
+            match self.discriminator.cmp(&R::INDEX_COUNT_HINT) {
+                Ordering::Less => {
+                    let discriminator = self.discriminator;
+                    self.discriminator += 1;
+                    // Indexes left unchanged by a replacement produce no operation.
+                    if let Err(e) = self.index_ops(discriminator) {
+                        return Some(Err(e));
+                    }
+                }
+                Ordering::Equal => {
+                    self.discriminator += 1;
+                    return Some(self.main_op());
+                }
+                Ordering::Greater => return None,
             }
-            Ordering::Greater => None,
         }
     }
 }
 
-impl<R, U> RecordOps<'_, R, U>
+impl<'r, R, U> RecordOps<'r, R, U>
 where
     R: DatabaseEntry,
     R::Key: RecordKey<Record = R>,
@@ -75,28 +91,92 @@ where
         Ok(self.cached_key_value.insert(kv).clone())
     }
 
-    fn index_op(&mut self, discriminator: u8) -> Result<BatchOp<U>, TransactionError<U>> {
-        let mut key_buf = <U::KeyUnifier as Unifier>::D::default();
+    /// Sets the version of the record that is being replaced by this write.
+    #[must_use]
+    pub fn with_previous(mut self, previous: Option<&'r R>) -> Self {
+        self.previous = previous;
+        self
+    }
+
+    /// Serializes the keys of all entries of `record` in the index, sorted and without duplicates.
+    fn index_entry_keys(
+        &mut self,
+        record: &R,
+        discriminator: u8,
+    ) -> Result<Vec<<U::KeyUnifier as Unifier>::D>, TransactionError<U>> {
+        let mut prefix = <U::KeyUnifier as Unifier>::D::default();
         self.key_unifier.serialize(
-            &mut key_buf,
+            &mut prefix,
             &WrapPrelude::new::<R>(Subtable::Index(discriminator)),
         )?;
-        self.record
-            .index_key(&mut key_buf, discriminator, &self.key_unifier)?;
-        let key_bytes = self.cached_key()?;
-        key_buf
-            .extend_from(key_bytes.as_view())
-            .map_err(BufferOverflowOr::overflow)?;
-        match self.op {
-            PreBufferOps::Insert | PreBufferOps::Put => {
-                let value = self.cached_key_value()?;
-                Ok(BatchOp::Insert {
-                    key: key_buf,
-                    value,
-                })
+        let mut entries = Vec::new();
+        record.index_entries(&prefix, discriminator, &self.key_unifier, &mut entries)?;
+        // Unique index values identify a single record, so they are stored without the primary key.
+        if !R::UNIQUE_INDEXES.contains(&discriminator) {
+            let key_bytes = self.cached_key()?;
+            for entry in &mut entries {
+                entry
+                    .extend_from(key_bytes.as_view())
+                    .map_err(BufferOverflowOr::overflow)?;
             }
-            PreBufferOps::Delete => Ok(BatchOp::Delete { key: key_buf }),
         }
+        entries.sort_unstable();
+        entries.dedup();
+        Ok(entries)
+    }
+
+    /// Serializes the value of `record`'s entries in the index, the primary key unless the index is covering.
+    fn index_value(
+        &mut self,
+        record: &R,
+        discriminator: u8,
+    ) -> Result<<U::ValueUnifier as Unifier>::D, TransactionError<U>> {
+        if !R::COVERING_INDEXES.contains(&discriminator) {
+            return self.cached_key_value();
+        }
+        let mut value = <U::ValueUnifier as Unifier>::D::default();
+        record
+            .index_value(&mut value, discriminator, self.key, &self.value_unifier)
+            .map_err(TransactionError::from_value)?;
+        Ok(value)
+    }
+
+    /// Queues the operations on the entries of the index, deleting the stale entries of the previous version
+    /// and writing the entries it didn't hold.
+    fn index_ops(&mut self, discriminator: u8) -> Result<(), TransactionError<U>> {
+        let current = self.index_entry_keys(self.record, discriminator)?;
+        if let PreBufferOps::Delete = self.op {
+            self.pending
+                .extend(current.into_iter().rev().map(|key| BatchOp::Delete { key }));
+            return Ok(());
+        }
+
+        let value = self.index_value(self.record, discriminator)?;
+        let (stale, rewrite) = match self.previous {
+            Some(previous) => (
+                self.index_entry_keys(previous, discriminator)?,
+                // Entries of covering indexes are rewritten when their projection changes.
+                R::COVERING_INDEXES.contains(&discriminator)
+                    && self.index_value(previous, discriminator)? != value,
+            ),
+            None => (Vec::new(), false),
+        };
+        let deletes = stale
+            .iter()
+            .filter(|key| current.binary_search(key).is_err())
+            .cloned()
+            .map(|key| BatchOp::Delete { key })
+            .collect::<Vec<_>>();
+        let inserts = current
+            .into_iter()
+            .filter(|key| rewrite || stale.binary_search(key).is_err())
+            .map(|key| BatchOp::Insert {
+                key,
+                value: value.clone(),
+            });
+        self.pending.extend(deletes.into_iter().chain(inserts));
+        self.pending.reverse();
+        Ok(())
     }
 
     fn main_op(&mut self) -> Result<BatchOp<U>, TransactionError<U>> {
@@ -107,9 +187,16 @@ where
         match self.op {
             PreBufferOps::Insert | PreBufferOps::Put => {
                 let mut value_buf = <U::ValueUnifier as Unifier>::D::default();
-                self.value_unifier
-                    .serialize(&mut value_buf, self.record)
-                    .map_err(TransactionError::from_value)?;
+                if R::ENVELOPE {
+                    let envelope = Envelope {
+                        version: R::VERSION,
+                        payload: self.record,
+                    };
+                    self.value_unifier.serialize(&mut value_buf, &envelope)
+                } else {
+                    self.value_unifier.serialize(&mut value_buf, self.record)
+                }
+                .map_err(TransactionError::from_value)?;
                 Ok(BatchOp::Insert {
                     key: key_buf,
                     value: value_buf,
@@ -139,10 +226,12 @@ where
     RecordOps {
         record,
         key,
+        previous: None,
         key_unifier: unifiers.key_unifier(),
         value_unifier: unifiers.value_unifier(),
         op,
         discriminator: 0,
+        pending: Vec::new(),
         cached_key: None,
         cached_key_value: None,
     }
@@ -1,17 +1,47 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
 use crate::{
-    ApplyError, DatabaseEntry, DatabaseError, DeriveKey, Incrementable, Manifest, Manifests,
-    RecordKey, Repository, Storage, UnifierPair,
+    ApplyError, BatchOp, DatabaseEntry, DatabaseError, DeriveKey, Incrementable, Manifest,
+    Manifests, RecordKey, Repository, Storage, Unified, Unifier, UnifierPair,
     transaction::{buffer::PreBufferOps, errors::TransactionError},
+    wrap::{Subtable, WrapPrelude, sequence_wrap},
 };
 
 use super::buffer::TransactionBuffer;
 
+type KeyBuffer<U> = <<U as UnifierPair>::KeyUnifier as Unifier>::D;
+type ValueBuffer<U> = <<U as UnifierPair>::ValueUnifier as Unifier>::D;
+
+/// A unique index entry written for the primary key it holds, or deleted if `None`.
+type UniqueClaim<U> = (KeyBuffer<U>, Option<Owner<U>>);
+
+/// Checks whether a stored entry of the unique index with the given discriminator holds the serialized primary key.
+type HeldBy<U> =
+    fn(&<U as UnifierPair>::ValueUnifier, u8, &ValueBuffer<U>, &ValueBuffer<U>) -> bool;
+
+/// The serialized primary key claiming a unique index entry, with the check against the stored owner of the entry.
+#[derive(Clone)]
+struct Owner<U: UnifierPair> {
+    key: ValueBuffer<U>,
+    discriminator: u8,
+    held_by: HeldBy<U>,
+}
+
 /// A database transaction that accumulates typed records in a pre-buffer and serializes
 /// them one at a time directly to storage on commit.
 ///
 /// This struct is always available, but the `commit` method is only available when the "atomic" feature is enabled.
 pub struct DatabaseTransaction<M: Manifest<U>, U: UnifierPair + 'static> {
     pre_buffer: TransactionBuffer<M, U>,
+    /// Unique index entries touched by the transaction, in order, checked against storage on commit.
+    unique_claims: Vec<UniqueClaim<U>>,
+    /// Entries deleted before the records are written, such as the stale indexes of migrated records.
+    deletions: Vec<KeyBuffer<U>>,
+    /// Latest value of every reserved entry written by the transaction, such as the sequences of the types put.
+    reserved: Vec<(KeyBuffer<U>, ValueBuffer<U>)>,
     unifiers: U,
 }
 
@@ -21,6 +51,9 @@ impl<M: Manifest<U>, U: UnifierPair + 'static> DatabaseTransaction<M, U> {
     pub fn new(unifiers: U) -> Self {
         Self {
             pre_buffer: TransactionBuffer::<M, U>::empty(),
+            unique_claims: Vec::new(),
+            deletions: Vec::new(),
+            reserved: Vec::new(),
             unifiers,
         }
     }
@@ -36,15 +69,45 @@ impl<M: Manifest<U>, U: UnifierPair + 'static> DatabaseTransaction<M, U> {
         M: Manifests<R>,
     {
         let original_key = R::key(&record);
+        self.track_unique(&original_key, &record, true)?;
         self.pre_buffer
             .push(PreBufferOps::Insert, (original_key.clone(), record));
         Ok(original_key)
     }
 
+    /// Writes `record` under `key`, replacing `previous`, the version currently stored there.
+    ///
+    /// Index entries of `previous` that `record` no longer produces are deleted, unchanged ones are left in place.
     /// # Errors
     ///
-    /// Returns a [`DatabaseError`] if writing to the underlying storage fails.
+    /// Returns a [`TransactionError`] if serializing keys or values fails while preparing the writes.
+    pub fn replace<R>(
+        &mut self,
+        key: R::Key,
+        record: R,
+        previous: R,
+    ) -> Result<(), TransactionError<U>>
+    where
+        R: DatabaseEntry + Clone + 'static,
+        R::Key: RecordKey<Record = R> + 'static,
+        for<'f> &'f (R::Key, R, R): Into<M::Record<'f>>,
+        M: Manifests<R>,
+    {
+        self.track_unique(&key, &previous, false)?;
+        self.track_unique(&key, &record, true)?;
+        self.pre_buffer
+            .push(PreBufferOps::Insert, (key, record, previous));
+        Ok(())
+    }
+
+    /// Writes `record` under the key following the last one assigned to its type.
+    ///
+    /// The assigned key is persisted as the type's sequence together with the record,
+    /// so keys are never reused, even after the records holding them are removed.
+    /// # Errors
     ///
+    /// Returns [`DatabaseError::FailedToIncrement`] if the key type has no value left,
+    /// or a [`DatabaseError`] if serializing the key fails.
     pub fn put<S, R>(&mut self, record: R, manifest: &mut M) -> Result<R::Key, DatabaseError<S>>
     where
         S: Storage<Unifiers = U>,
@@ -60,6 +123,8 @@ impl<M: Manifest<U>, U: UnifierPair + 'static> DatabaseTransaction<M, U> {
             R::Key::default()
         };
 
+        self.track_unique(&new_key, &record, true)?;
+        self.track_sequence::<R>(&new_key)?;
         self.pre_buffer
             .push(PreBufferOps::Put, (new_key.clone(), record));
         last_key.replace(new_key.clone());
@@ -76,15 +141,49 @@ impl<M: Manifest<U>, U: UnifierPair + 'static> DatabaseTransaction<M, U> {
         for<'f> &'f (R::Key, R): Into<M::Record<'f>>,
         M: Manifests<R>,
     {
+        self.track_unique(key, record, false)?;
         self.pre_buffer
             .push(PreBufferOps::Delete, (key.clone(), record.clone()));
         Ok(())
     }
 
+    /// Writes `record` under `key`, together with its index entries, without replacing a previous version.
+    ///
+    /// Used by migrations, which delete the index entries of the previous versions separately.
+    pub(crate) fn rewrite<R>(&mut self, key: R::Key, record: R) -> Result<(), TransactionError<U>>
+    where
+        R: DatabaseEntry + 'static,
+        R::Key: RecordKey<Record = R> + 'static,
+        for<'f> &'f (R::Key, R): Into<M::Record<'f>>,
+        M: Manifests<R>,
+    {
+        self.track_unique(&key, &record, true)?;
+        self.pre_buffer.push(PreBufferOps::Insert, (key, record));
+        Ok(())
+    }
+
+    /// Deletes a raw entry before any record of the transaction is written.
+    ///
+    /// Entries of unique indexes are released, so that records of the transaction can claim their values.
+    pub(crate) fn delete_entry(&mut self, key: KeyBuffer<U>, unique: bool) {
+        if unique {
+            self.unique_claims.push((key.clone(), None));
+        }
+        self.deletions.push(key);
+    }
+
+    /// Writes a raw entry of a reserved subtable after the records, replacing an earlier value of the same entry.
+    pub(crate) fn set_reserved(&mut self, key: KeyBuffer<U>, value: ValueBuffer<U>) {
+        match self.reserved.iter_mut().find(|(known, _)| *known == key) {
+            Some((_, last)) => *last = value,
+            None => self.reserved.push((key, value)),
+        }
+    }
+
     /// Returns true if the transaction has no pending operations.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.pre_buffer.is_empty()
+        self.pre_buffer.is_empty() && self.deletions.is_empty() && self.reserved.is_empty()
     }
 
     /// Commits all pending operations to the storage.
@@ -97,7 +196,8 @@ impl<M: Manifest<U>, U: UnifierPair + 'static> DatabaseTransaction<M, U> {
     ///
     /// # Errors
     ///
-    /// Returns a [`DatabaseError`] if serialisation of any record fails or if the
+    /// Returns [`DatabaseError::UniqueViolation`] if a unique index value would be owned by two records,
+    /// in which case nothing is written, or a [`DatabaseError`] if serialisation of any record fails or if the
     /// underlying storage operation fails.
     pub fn commit<S>(self, storage: &mut S) -> Result<(), DatabaseError<S>>
     where
@@ -109,18 +209,136 @@ impl<M: Manifest<U>, U: UnifierPair + 'static> DatabaseTransaction<M, U> {
 
         let DatabaseTransaction {
             pre_buffer,
+            unique_claims,
+            deletions,
+            reserved,
             unifiers,
         } = self;
+        Self::check_unique(unique_claims, storage, unifiers)?;
 
+        let deletions = deletions.into_iter().map(|key| Ok(BatchOp::Delete { key }));
+        let reserved = reserved
+            .into_iter()
+            .map(|(key, value)| Ok(BatchOp::Insert { key, value }));
         storage
             .repository_mut()
-            .apply(pre_buffer.into_iter(unifiers))
+            .apply(
+                deletions
+                    .chain(pre_buffer.into_iter(unifiers))
+                    .chain(reserved),
+            )
             .map_err(|e| match e {
                 ApplyError::Serialization(err) => DatabaseError::from_transaction_error(err),
                 ApplyError::Application(storage_err) => DatabaseError::Storage(storage_err),
             })
     }
 
+    /// Records `key` as the last one assigned to records of type `R`, to be persisted on commit.
+    fn track_sequence<R: DatabaseEntry>(
+        &mut self,
+        key: &R::Key,
+    ) -> Result<(), TransactionError<U>> {
+        let mut entry = KeyBuffer::<U>::default();
+        sequence_wrap::<R, _>(&self.unifiers.key_unifier(), &mut entry)?;
+        let mut value = ValueBuffer::<U>::default();
+        self.unifiers
+            .value_unifier()
+            .serialize(&mut value, key)
+            .map_err(TransactionError::from_value)?;
+        self.set_reserved(entry, value);
+        Ok(())
+    }
+
+    /// Records the unique index entries of `record`, written for `key` if `claim` is set and deleted otherwise.
+    fn track_unique<R: DatabaseEntry>(
+        &mut self,
+        key: &R::Key,
+        record: &R,
+        claim: bool,
+    ) -> Result<(), TransactionError<U>> {
+        if R::UNIQUE_INDEXES.is_empty() {
+            return Ok(());
+        }
+        let key_unifier = self.unifiers.key_unifier();
+        let owner = if claim {
+            let mut owner = ValueBuffer::<U>::default();
+            self.unifiers
+                .value_unifier()
+                .serialize(&mut owner, key)
+                .map_err(TransactionError::from_value)?;
+            Some(owner)
+        } else {
+            None
+        };
+        for &discriminator in R::UNIQUE_INDEXES {
+            let owner = owner.clone().map(|key| Owner {
+                key,
+                discriminator,
+                held_by: Self::held_by::<R>,
+            });
+            let mut prefix = KeyBuffer::<U>::default();
+            key_unifier.serialize(
+                &mut prefix,
+                &WrapPrelude::new::<R>(Subtable::Index(discriminator)),
+            )?;
+            let mut entries = Vec::new();
+            record.index_entries(&prefix, discriminator, &key_unifier, &mut entries)?;
+            self.unique_claims
+                .extend(entries.into_iter().map(|entry| (entry, owner.clone())));
+        }
+        Ok(())
+    }
+
+    /// Checks that every unique index entry written by the transaction is free or already held by the same record,
+    /// taking both the stored entries and the earlier operations of the transaction into account.
+    fn check_unique<S>(
+        claims: Vec<UniqueClaim<U>>,
+        storage: &S,
+        unifiers: U,
+    ) -> Result<(), DatabaseError<S>>
+    where
+        S: Storage<Unifiers = U>,
+    {
+        let value_unifier = unifiers.value_unifier();
+        // Owners of the entries touched so far, which take precedence over storage.
+        let mut owners: BTreeMap<KeyBuffer<U>, Option<ValueBuffer<U>>> = BTreeMap::new();
+        for (entry, owner) in claims {
+            if let Some(current) = owners.get_mut(&entry) {
+                if let (Some(current), Some(owner)) = (&*current, &owner)
+                    && *current != owner.key
+                {
+                    return Err(DatabaseError::UniqueViolation);
+                }
+                *current = owner.map(|owner| owner.key);
+                continue;
+            }
+            if let Some(owner) = &owner
+                && let Some(current) = storage
+                    .repository()
+                    .get_entry(entry.as_view())
+                    .map_err(DatabaseError::Storage)?
+                && !(owner.held_by)(&value_unifier, owner.discriminator, &current, &owner.key)
+            {
+                return Err(DatabaseError::UniqueViolation);
+            }
+            owners.insert(entry, owner.map(|owner| owner.key));
+        }
+        Ok(())
+    }
+
+    /// Checks whether a stored entry of a unique index of `R` holds the serialized primary key `owner`,
+    /// comparing the deserialized keys, read together with the projection of covering indexes.
+    fn held_by<R: DatabaseEntry>(
+        unifier: &U::ValueUnifier,
+        discriminator: u8,
+        value: &ValueBuffer<U>,
+        owner: &ValueBuffer<U>,
+    ) -> bool {
+        let stored = R::index_owner(value, discriminator, unifier);
+        let owner = unifier.deserialize::<R::Key>(owner);
+        matches!((stored, owner), (Ok(stored), Ok(owner)) if stored == owner)
+    }
+
     /// Discards all pending operations without applying them.
     /// The transaction is consumed by this operation.
     pub fn rollback(self) {
@@ -1,14 +1,59 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::string::String;
 use core::fmt;
+use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
-use std::marker::PhantomData;
 
-use serde::{Serialize, de::Visitor, ser::SerializeTuple};
+use serde::{
+    Serialize,
+    de::{Error, SeqAccess, Visitor},
+    ser::SerializeTuple,
+};
 
+use crate::{BufferOverflowError, Unified};
+
+/// A string that serializes to bytes ordered like the string itself, with any serializer
+/// that writes `u8` tuple elements as single bytes, such as bincode and postcard.
+///
+/// The bytes `0x00` and `0x01` are escaped as `0x01 0x01` and `0x01 0x02`, and the string is terminated
+/// by a single `0x00`, so a prefix always sorts before the strings extending it.
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Lexicographic<S: StringLike>(S);
 
-pub trait StringLike: AsRef<str> + for<'a> From<&'a str> + for<'a> Extend<&'a str> {}
-impl<S: AsRef<str> + for<'a> From<&'a str> + for<'a> Extend<&'a str>> StringLike for S {}
+/// A byte string with the same order-preserving encoding as [`Lexicographic`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LexicographicBytes<B: BytesLike>(B);
+
+/// String buffers that [`Lexicographic`] can decode into.
+pub trait StringLike: AsRef<str> + Default {
+    /// Appends `part` to the string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string can't hold `part`.
+    fn extend_from(&mut self, part: &str) -> Result<(), BufferOverflowError>;
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl StringLike for String {
+    fn extend_from(&mut self, part: &str) -> Result<(), BufferOverflowError> {
+        self.try_reserve(part.len())
+            .map_err(|_| BufferOverflowError)?;
+        self.push_str(part);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> StringLike for heapless::String<N> {
+    fn extend_from(&mut self, part: &str) -> Result<(), BufferOverflowError> {
+        self.push_str(part).map_err(|_| BufferOverflowError)
+    }
+}
+
+/// Byte buffers that [`LexicographicBytes`] can decode into, such as `Vec<u8>` and `heapless::Vec<u8, N>`.
+pub trait BytesLike: for<'a> Unified<View<'a> = &'a [u8]> + AsRef<[u8]> {}
+impl<B: for<'a> Unified<View<'a> = &'a [u8]> + AsRef<[u8]>> BytesLike for B {}
 
 impl<S: StringLike> Lexicographic<S> {
     /// Creates a new `LexicographicString` from a `String`.
@@ -36,7 +81,7 @@ impl<S: StringLike> AsRef<str> for Lexicographic<S> {
     }
 }
 
-impl<S: StringLike> From<&str> for Lexicographic<S> {
+impl<S: StringLike + for<'a> From<&'a str>> From<&str> for Lexicographic<S> {
     fn from(s: &str) -> Self {
         Lexicographic(s.into())
     }
@@ -56,20 +101,12 @@ impl<S: StringLike> DerefMut for Lexicographic<S> {
     }
 }
 
-impl<S: StringLike> Serialize for Lexicographic<S>
-where
-    S: AsRef<str>,
-{
+impl<S: StringLike> Serialize for Lexicographic<S> {
     fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
     where
         Ser: serde::Serializer,
     {
-        let mut s = serializer.serialize_tuple(self.0.as_ref().len())?;
-        for byte in self.0.as_ref().as_bytes() {
-            s.serialize_element(byte)?;
-        }
-        s.serialize_element(&b'\0')?;
-        s.end()
+        serialize_escaped(self.0.as_ref().as_bytes(), serializer)
     }
 }
 
@@ -82,65 +119,170 @@ impl<'de, S: StringLike> serde::Deserialize<'de> for Lexicographic<S> {
     }
 }
 
-struct LexicographicStringVisitor<S>(PhantomData<S>);
+impl<B: BytesLike> LexicographicBytes<B> {
+    /// Creates a new `LexicographicBytes` from a byte buffer.
+    #[must_use]
+    pub fn new(bytes: B) -> Self {
+        LexicographicBytes(bytes)
+    }
 
-impl<'de, S: StringLike> Visitor<'de> for LexicographicStringVisitor<S> {
-    type Value = Lexicographic<S>;
+    /// Consumes the wrapper and returns the byte buffer.
+    #[must_use]
+    pub fn into_inner(self) -> B {
+        self.0
+    }
+}
 
-    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("a lexicographically ordered string")
+impl<B: BytesLike> AsRef<[u8]> for LexicographicBytes<B> {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
     }
+}
 
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+impl<B: BytesLike> From<B> for LexicographicBytes<B> {
+    fn from(bytes: B) -> Self {
+        LexicographicBytes(bytes)
+    }
+}
+
+impl<B: BytesLike> Deref for LexicographicBytes<B> {
+    type Target = B;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<B: BytesLike> DerefMut for LexicographicBytes<B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<B: BytesLike> Serialize for LexicographicBytes<B> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
     where
-        A: serde::de::SeqAccess<'de>,
+        Ser: serde::Serializer,
     {
-        let mut bytes = [0u8; 256];
-        let mut i = 0;
+        serialize_escaped(self.0.as_ref(), serializer)
+    }
+}
 
-        let mut value = S::from("");
+impl<'de, B: BytesLike> serde::Deserialize<'de> for LexicographicBytes<B> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(usize::MAX, LexicographicBytesVisitor::<B>(PhantomData))
+    }
+}
 
-        while let Some(byte) = seq.next_element::<u8>()? {
-            if byte == 0 {
-                break;
+/// Serializes `bytes` as a tuple of escaped bytes followed by the terminator.
+pub(crate) fn serialize_escaped<Ser: serde::Serializer>(
+    bytes: &[u8],
+    serializer: Ser,
+) -> Result<Ser::Ok, Ser::Error> {
+    let escaped = bytes.iter().filter(|byte| **byte <= 1).count();
+    let mut s = serializer.serialize_tuple(bytes.len() + escaped + 1)?;
+    for byte in bytes {
+        match byte {
+            0 => {
+                s.serialize_element(&1u8)?;
+                s.serialize_element(&1u8)?;
             }
-            bytes[i] = byte;
-            i += 1;
-
-            if i < bytes.len() {
-                continue;
+            1 => {
+                s.serialize_element(&1u8)?;
+                s.serialize_element(&2u8)?;
             }
+            _ => s.serialize_element(byte)?,
+        }
+    }
+    s.serialize_element(&0u8)?;
+    s.end()
+}
 
-            let Some(utf8_chunk) = bytes.utf8_chunks().next() else {
-                return Err(serde::de::Error::custom("empty UTF-8 chunk"));
-            };
+/// Reads the next unescaped byte, or `None` once the terminator is reached.
+pub(crate) fn next_unescaped<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<Option<u8>, A::Error> {
+    let mut element = || {
+        seq.next_element::<u8>()?
+            .ok_or_else(|| A::Error::custom("unterminated lexicographic value"))
+    };
+    match element()? {
+        0 => Ok(None),
+        1 => match element()? {
+            1 => Ok(Some(0)),
+            2 => Ok(Some(1)),
+            byte => Err(A::Error::custom(format_args!(
+                "invalid lexicographic escape {byte:#04x}"
+            ))),
+        },
+        byte => Ok(Some(byte)),
+    }
+}
 
-            value.extend(core::iter::once(utf8_chunk.valid()));
+struct LexicographicStringVisitor<S>(PhantomData<S>);
 
-            let valid = utf8_chunk.valid().len();
-            bytes.copy_within(valid..i, 0);
-            i -= valid;
-        }
+impl<'de, S: StringLike> Visitor<'de> for LexicographicStringVisitor<S> {
+    type Value = Lexicographic<S>;
 
-        if i > 0 {
-            let Some(utf8_chunk) = bytes[..i].utf8_chunks().next() else {
-                return Err(serde::de::Error::custom("empty UTF-8 chunk"));
-            };
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a lexicographically ordered string")
+    }
 
-            if !utf8_chunk.invalid().is_empty() {
-                return Err(serde::de::Error::custom("invalid UTF-8 at end of string"));
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut value = S::default();
+        // Bytes of a character whose encoding is not complete yet, at most four in UTF-8.
+        let mut pending = [0u8; 4];
+        let mut len = 0;
+
+        while let Some(byte) = next_unescaped(&mut seq)? {
+            pending[len] = byte;
+            len += 1;
+            match core::str::from_utf8(&pending[..len]) {
+                Ok(character) => {
+                    value.extend_from(character).map_err(A::Error::custom)?;
+                    len = 0;
+                }
+                Err(e) if e.error_len().is_none() => {}
+                Err(_) => return Err(A::Error::custom("invalid UTF-8 in string")),
             }
+        }
 
-            value.extend(core::iter::once(utf8_chunk.valid()));
+        if len > 0 {
+            return Err(A::Error::custom("invalid UTF-8 at end of string"));
         }
+        Ok(Lexicographic(value))
+    }
+}
+
+struct LexicographicBytesVisitor<B>(PhantomData<B>);
+
+impl<'de, B: BytesLike> Visitor<'de> for LexicographicBytesVisitor<B> {
+    type Value = LexicographicBytes<B>;
 
-        Ok(Lexicographic::<S>(value))
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a lexicographically ordered byte string")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut value = B::default();
+        while let Some(byte) = next_unescaped(&mut seq)? {
+            value.extend_from(&[byte]).map_err(A::Error::custom)?;
+        }
+        Ok(LexicographicBytes(value))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const CONFIG: bincode::config::Configuration = bincode::config::standard();
 
@@ -216,4 +358,98 @@ mod tests {
         assert_eq!(original, deserialized);
         Ok(())
     }
+
+    #[test]
+    fn test_embedded_nul_is_escaped() -> Result<(), Box<dyn std::error::Error>> {
+        let original = Lexicographic::<String>::from("a\0\x01b");
+        let serialized = bincode::serde::encode_to_vec(&original, CONFIG)?;
+        assert_eq!(serialized, [b'a', 1, 1, 1, 2, b'b', 0].to_vec());
+        let (deserialized, _): (Lexicographic<String>, _) =
+            bincode::serde::decode_from_slice(&serialized, CONFIG)?;
+        assert_eq!(original, deserialized);
+
+        assert!(is_less::<String>(&"a".into(), &"a\0".into())?);
+        assert!(is_less::<String>(&"a\0".into(), &"a\x01".into())?);
+        assert!(is_less::<String>(&"a\x01".into(), &"a\x02".into())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_long_strings_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+        let original = Lexicographic::<String>::new("żółw🐢".repeat(200));
+        let serialized = postcard::to_allocvec(&original)?;
+        let deserialized: Lexicographic<String> = postcard::from_bytes(&serialized)?;
+        assert_eq!(original, deserialized);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_escape() {
+        let result =
+            bincode::serde::decode_from_slice::<Lexicographic<String>, _>(&[b'a', 1, 3, 0], CONFIG);
+        assert!(result.is_err());
+        let result = bincode::serde::decode_from_slice::<Lexicographic<String>, _>(b"a", CONFIG);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bytes_serialization() -> Result<(), Box<dyn std::error::Error>> {
+        let original = LexicographicBytes::new(vec![0u8, 1, 2, 255]);
+        let serialized = postcard::to_allocvec(&original)?;
+        assert_eq!(serialized, [1, 1, 1, 2, 2, 255, 0].to_vec());
+        let deserialized: LexicographicBytes<Vec<u8>> = postcard::from_bytes(&serialized)?;
+        assert_eq!(original, deserialized);
+        Ok(())
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_heapless_buffers() -> Result<(), Box<dyn std::error::Error>> {
+        let original = Lexicographic::new(heapless::String::<8>::try_from("kivis")?);
+        let serialized = postcard::to_allocvec(&original)?;
+        let deserialized: Lexicographic<heapless::String<8>> = postcard::from_bytes(&serialized)?;
+        assert_eq!(original, deserialized);
+        assert!(postcard::from_bytes::<Lexicographic<heapless::String<4>>>(&serialized).is_err());
+
+        let bytes = LexicographicBytes::new(heapless::Vec::<u8, 4>::from_slice(&[0, 7])?);
+        let serialized = bincode::serde::encode_to_vec(&bytes, CONFIG)?;
+        let (deserialized, _): (LexicographicBytes<heapless::Vec<u8, 4>>, _) =
+            bincode::serde::decode_from_slice(&serialized, CONFIG)?;
+        assert_eq!(bytes, deserialized);
+        Ok(())
+    }
+
+    proptest! {
+        /// Encodings compare like the strings under both bincode and postcard.
+        #[test]
+        fn string_order_is_preserved(a in "\\PC*|[\\x00-\\x02a]{0,6}", b in "\\PC*|[\\x00-\\x02a]{0,6}") {
+            let (a, b) = (Lexicographic::<String>::new(a), Lexicographic::<String>::new(b));
+            let bincode_a = bincode::serde::encode_to_vec(&a, CONFIG).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let bincode_b = bincode::serde::encode_to_vec(&b, CONFIG).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let postcard_a = postcard::to_allocvec(&a).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let postcard_b = postcard::to_allocvec(&b).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(a.cmp(&b), bincode_a.cmp(&bincode_b));
+            prop_assert_eq!(a.cmp(&b), postcard_a.cmp(&postcard_b));
+
+            let (decoded, _): (Lexicographic<String>, _) = bincode::serde::decode_from_slice(&bincode_a, CONFIG).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(&decoded, &a);
+            let decoded: Lexicographic<String> = postcard::from_bytes(&postcard_b).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(&decoded, &b);
+        }
+
+        /// Encodings compare like the byte strings under both bincode and postcard.
+        #[test]
+        fn bytes_order_is_preserved(a in prop::collection::vec(0u8..4, 0..8), b in any::<Vec<u8>>()) {
+            let (a, b) = (LexicographicBytes::new(a), LexicographicBytes::new(b));
+            let bincode_a = bincode::serde::encode_to_vec(&a, CONFIG).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let bincode_b = bincode::serde::encode_to_vec(&b, CONFIG).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let postcard_a = postcard::to_allocvec(&a).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let postcard_b = postcard::to_allocvec(&b).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(a.cmp(&b), bincode_a.cmp(&bincode_b));
+            prop_assert_eq!(a.cmp(&b), postcard_a.cmp(&postcard_b));
+
+            let decoded: LexicographicBytes<Vec<u8>> = postcard::from_bytes(&postcard_a).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(decoded, a);
+        }
+    }
 }
@@ -1,5 +1,4 @@
 use std::{
-    cmp::Reverse,
     collections::BTreeMap,
     error::Error,
     fmt::{Debug, Display},
@@ -11,13 +10,13 @@ use bincode::{
     error::{DecodeError, EncodeError},
 };
 
-use crate::{BufferOverflowError, Repository, Storage};
+use crate::{BufferOverflowError, Direction, OrderedUnifier, Repository, Storage};
 
 /// A memory-based storage implementation using a [`BTreeMap`].
 ///
-/// This storage backend keeps all data in memory and uses reverse-ordered keys
-/// for efficient range queries. Implements the [`Storage`] trait to be used as a storage backend.
-pub type MemoryStorage = BTreeMap<Reverse<Vec<u8>>, Vec<u8>>;
+/// This storage backend keeps all data in memory, ordered by key, and scans ranges in either direction.
+/// Implements the [`Storage`] trait to be used as a storage backend.
+pub type MemoryStorage = BTreeMap<Vec<u8>, Vec<u8>>;
 
 /// Error type for [`MemoryStorage`] operations.
 #[derive(Debug)]
@@ -72,9 +71,16 @@ impl From<BufferOverflowError> for MemoryStorageError {
     }
 }
 
+/// Clamps the end of a range to its start, so that an inverted range is empty instead of
+/// making [`BTreeMap::range`] panic.
+fn ordered(range: Range<Vec<u8>>) -> Range<Vec<u8>> {
+    let end = range.end.max(range.start.clone());
+    range.start..end
+}
+
 impl Storage for MemoryStorage {
     type Repo = Self;
-    type Unifiers = (Configuration, Configuration);
+    type Unifiers = (OrderedUnifier, Configuration);
     fn repository(&self) -> &Self::Repo {
         self
     }
@@ -90,25 +96,41 @@ impl Repository for MemoryStorage {
     type Error = MemoryStorageError;
 
     fn insert_entry(&mut self, key: &[u8], value: &[u8]) -> Result<(), Self::Error> {
-        self.insert(Reverse(key.to_vec()), value.to_vec());
+        self.insert(key.to_vec(), value.to_vec());
         Ok(())
     }
 
     fn get_entry(&self, key: &[u8]) -> Result<Option<Self::V>, Self::Error> {
-        Ok(self.get(&Reverse(key.to_vec())).cloned())
+        Ok(self.get(key).cloned())
     }
 
     fn remove_entry(&mut self, key: &[u8]) -> Result<Option<Self::V>, Self::Error> {
-        Ok(self.remove(&Reverse(key.to_vec())))
+        Ok(self.remove(key))
+    }
+
+    fn contains_entry(&self, key: &[u8]) -> Result<bool, Self::Error> {
+        Ok(self.contains_key(key))
+    }
+
+    fn count_range(&self, range: Range<Self::K>) -> Result<usize, Self::Error> {
+        Ok(self.range(ordered(range)).count())
     }
 
     fn scan_range(
         &self,
         range: Range<Self::K>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = Result<Self::K, Self::Error>>, Self::Error> {
-        let reverse_range = Reverse(range.end)..Reverse(range.start);
+        let iter = direction.apply(self.range(ordered(range)));
+        Ok(iter.map(|(k, _v)| Ok(k.clone())))
+    }
 
-        let iter = self.range(reverse_range);
-        Ok(iter.map(|(k, _v)| Ok(k.0.clone())))
+    fn scan_range_entries(
+        &self,
+        range: Range<Self::K>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = Result<(Self::K, Self::V), Self::Error>>, Self::Error> {
+        let iter = direction.apply(self.range(ordered(range)));
+        Ok(iter.map(|(k, v)| Ok((k.clone(), v.clone()))))
     }
 }
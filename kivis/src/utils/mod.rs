@@ -1,7 +1,15 @@
 mod lexicographic;
 #[cfg(feature = "memory-storage")]
 mod memory;
+#[cfg(any(feature = "std", feature = "alloc"))]
+mod order;
+#[cfg(any(feature = "std", feature = "alloc"))]
+mod reversed;
 
 pub use lexicographic::*;
 #[cfg(feature = "memory-storage")]
 pub use memory::{MemoryStorage, MemoryStorageError};
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use order::{OrderCheckError, check_order_preserving};
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use reversed::Reversed;
@@ -0,0 +1,100 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display};
+
+use serde::Serialize;
+
+use crate::{BufferOverflowOr, Unified, Unifier};
+
+/// Error returned by [`check_order_preserving`].
+pub enum OrderCheckError<U: Unifier> {
+    /// A value could not be serialized.
+    Serialization(BufferOverflowOr<U::SerError>),
+    /// The encodings of the values at `left` and `right` compare differently than the values.
+    Mismatch {
+        left: usize,
+        right: usize,
+        expected: Ordering,
+        encoded: Ordering,
+    },
+}
+
+impl<U: Unifier> Debug for OrderCheckError<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serialization(e) => f.debug_tuple("Serialization").field(e).finish(),
+            Self::Mismatch {
+                left,
+                right,
+                expected,
+                encoded,
+            } => f
+                .debug_struct("Mismatch")
+                .field("left", left)
+                .field("right", right)
+                .field("expected", expected)
+                .field("encoded", encoded)
+                .finish(),
+        }
+    }
+}
+
+impl<U: Unifier> Display for OrderCheckError<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serialization(e) => write!(f, "Serialization error: {e}"),
+            Self::Mismatch {
+                left,
+                right,
+                expected,
+                encoded,
+            } => write!(
+                f,
+                "Values {left} and {right} compare as {expected:?}, but their encodings as {encoded:?}"
+            ),
+        }
+    }
+}
+
+impl<U: Unifier> core::error::Error for OrderCheckError<U> {}
+
+/// Checks that `unifier` encodes every pair of `values` in the same order as [`Ord`] compares them.
+///
+/// Meant for property tests of [`OrderPreserving`](crate::OrderPreserving) implementations,
+/// with `values` coming from a generator.
+/// # Errors
+///
+/// Returns [`OrderCheckError::Mismatch`] for the first pair whose encodings are ordered differently
+/// than the values, or [`OrderCheckError::Serialization`] if a value can't be serialized.
+pub fn check_order_preserving<U, T>(unifier: &U, values: &[T]) -> Result<(), OrderCheckError<U>>
+where
+    U: Unifier,
+    T: Serialize + Ord,
+    for<'a> <U::D as Unified>::View<'a>: Ord,
+{
+    let mut encoded = Vec::with_capacity(values.len());
+    for value in values {
+        let mut buffer = U::D::default();
+        unifier
+            .serialize(&mut buffer, value)
+            .map_err(OrderCheckError::Serialization)?;
+        encoded.push(buffer);
+    }
+
+    for (left, (a, a_encoded)) in values.iter().zip(&encoded).enumerate() {
+        for (right, (b, b_encoded)) in values.iter().zip(&encoded).enumerate().skip(left + 1) {
+            let expected = a.cmp(b);
+            let actual = a_encoded.as_view().cmp(&b_encoded.as_view());
+            if expected != actual {
+                return Err(OrderCheckError::Mismatch {
+                    left,
+                    right,
+                    expected,
+                    encoded: actual,
+                });
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use serde::{
+    Deserialize, Serialize,
+    de::{DeserializeOwned, Error as _, SeqAccess, Visitor},
+    ser::Error as _,
+};
+
+use super::lexicographic::{next_unescaped, serialize_escaped};
+use crate::{OrderedUnifier, Unifier};
+
+/// A value that sorts in the opposite order of `T`, both in Rust and once serialized.
+///
+/// The value is encoded with [`OrderedUnifier`] and every byte of the encoding is inverted, then
+/// escaped like [`Lexicographic`](crate::Lexicographic). Inside `#[key]` and `#[index]` fields it gives
+/// descending scans, such as "newest first", or mixed-direction composite indexes like
+/// `(category, Reversed<score>)`, regardless of the direction a backend is scanned in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Reversed<T>(pub T);
+
+impl<T> Reversed<T> {
+    /// Wraps `value`, reversing its order.
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Reversed(value)
+    }
+
+    /// Consumes the wrapper and returns the value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Reversed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        other.0.partial_cmp(&self.0)
+    }
+}
+
+impl<T: Ord> Ord for Reversed<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl<T> From<T> for Reversed<T> {
+    fn from(value: T) -> Self {
+        Reversed(value)
+    }
+}
+
+impl<T> Deref for Reversed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Reversed<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Serialize> Serialize for Reversed<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut encoded = Vec::new();
+        OrderedUnifier::<Vec<u8>>::new()
+            .serialize(&mut encoded, &self.0)
+            .map_err(S::Error::custom)?;
+        // The ordered encoding is prefix-free, so inverting each byte inverts the order.
+        for byte in &mut encoded {
+            *byte = !*byte;
+        }
+        serialize_escaped(&encoded, serializer)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Reversed<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(usize::MAX, ReversedVisitor::<T>(PhantomData))
+    }
+}
+
+struct ReversedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: DeserializeOwned> Visitor<'de> for ReversedVisitor<T> {
+    type Value = Reversed<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value in reversed order")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut encoded = Vec::new();
+        while let Some(byte) = next_unescaped(&mut seq)? {
+            encoded.push(!byte);
+        }
+        OrderedUnifier::<Vec<u8>>::new()
+            .deserialize(&encoded)
+            .map(Reversed)
+            .map_err(A::Error::custom)
+    }
+}
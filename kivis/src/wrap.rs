@@ -9,6 +9,7 @@ type KeyRange<KU> = (<KU as Unifier>::D, <KU as Unifier>::D);
 pub(crate) enum Subtable {
     /// Main data storage subtable.
     Main,
+    /// Bookkeeping entries of the scope, see [`ReservedEntry`].
     Reserved,
     /// Index subtable with discriminator.
     Index(u8),
@@ -41,7 +42,7 @@ impl<'de> Deserialize<'de> for Subtable {
         let value = u8::deserialize(deserializer)?;
         match value {
             0 => Ok(Subtable::Main),
-            1 => Err(serde::de::Error::custom("Reserved subtable value 1")),
+            1 => Ok(Subtable::Reserved),
             n => Ok(Subtable::Index(n.checked_sub(2).ok_or_else(|| {
                 serde::de::Error::custom("Index discriminator underflow when subtracting 2")
             })?)),
@@ -66,6 +67,21 @@ impl WrapPrelude {
     }
 }
 
+/// Entries stored in the reserved subtable of a scope, after the [`WrapPrelude`].
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) enum ReservedEntry {
+    /// The last key assigned to a record of the scope by an autoincrementing put.
+    Sequence,
+    /// The fingerprint of the record type the scope was created for, or last migrated to.
+    Schema,
+    /// The version of the record type the values of the scope were written with.
+    Version,
+    /// The last key rewritten by a migration of the scope that hasn't finished yet.
+    Migration,
+    /// The number of indexes of the record type the scope was created for, or last migrated to.
+    Indexes,
+}
+
 /// Wraps a database entry key with scope and subtable information for storage.
 pub(crate) fn wrap<R: DatabaseEntry, KU: Unifier>(
     item_key: &R::Key,
@@ -79,14 +95,54 @@ pub(crate) fn wrap<R: DatabaseEntry, KU: Unifier>(
 
 pub(crate) fn empty_wrap<R: DatabaseEntry, KU: Unifier>(
     config: &KU,
+) -> Result<KeyRange<KU>, BufferOverflowOr<KU::SerError>> {
+    scope_wrap(R::SCOPE, config)
+}
+
+/// Serializes the bounds of the main subtable of `scope`, holding all of its records.
+pub(crate) fn scope_wrap<KU: Unifier>(
+    scope: u8,
+    config: &KU,
 ) -> Result<KeyRange<KU>, BufferOverflowOr<KU::SerError>> {
     let mut start_buffer = KU::D::default();
-    config.serialize(&mut start_buffer, &WrapPrelude::new::<R>(Subtable::Main))?;
+    let prelude = WrapPrelude {
+        scope,
+        subtable: Subtable::Main,
+    };
+    config.serialize(&mut start_buffer, &prelude)?;
     config.serialize(&mut start_buffer, &())?;
 
     let mut end_buffer = KU::D::default();
-    config.serialize(&mut end_buffer, &WrapPrelude::new::<R>(Subtable::Reserved))?;
+    let prelude = WrapPrelude {
+        scope,
+        subtable: Subtable::Reserved,
+    };
+    config.serialize(&mut end_buffer, &prelude)?;
     config.serialize(&mut end_buffer, &())?;
 
     Ok((start_buffer, end_buffer))
 }
+
+/// Serializes the key of the autoincrement sequence of a database entry type.
+pub(crate) fn sequence_wrap<R: DatabaseEntry, KU: Unifier>(
+    unifier: &KU,
+    buffer: &mut KU::D,
+) -> Result<(), BufferOverflowOr<KU::SerError>> {
+    reserved_wrap(R::SCOPE, &ReservedEntry::Sequence, unifier, buffer)
+}
+
+/// Serializes the key of an entry in the reserved subtable of `scope`.
+pub(crate) fn reserved_wrap<KU: Unifier>(
+    scope: u8,
+    entry: &ReservedEntry,
+    unifier: &KU,
+    buffer: &mut KU::D,
+) -> Result<(), BufferOverflowOr<KU::SerError>> {
+    let prelude = WrapPrelude {
+        scope,
+        subtable: Subtable::Reserved,
+    };
+    unifier.serialize(buffer, &prelude)?;
+    unifier.serialize(buffer, entry)?;
+    Ok(())
+}
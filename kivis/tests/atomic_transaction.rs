@@ -1,12 +1,12 @@
-use std::{cmp::Reverse, collections::BTreeMap, ops::Range};
+use std::{collections::BTreeMap, ops::Range};
 
 use bincode::{
     config::Configuration,
     error::{DecodeError, EncodeError},
 };
 use kivis::{
-    ApplyError, BufferOverflowError, Database, DatabaseTransaction, Record, Repository, Storage,
-    manifest,
+    ApplyError, BufferOverflowError, Database, DatabaseTransaction, Direction, Record, Repository,
+    Storage, manifest,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -31,7 +31,7 @@ manifest![Manifest: MockRecord];
 // Mock atomic storage implementation
 #[derive(Debug)]
 pub struct MockAtomicStorage {
-    data: BTreeMap<Reverse<Vec<u8>>, Vec<u8>>,
+    data: BTreeMap<Vec<u8>, Vec<u8>>,
     /// Fail on the (N+1)th write inside `apply`. Defaults to `usize::MAX` (never fails).
     fail_after: usize,
 }
@@ -69,25 +69,25 @@ impl Repository for MockAtomicStorage {
     type Error = MockError;
 
     fn insert_entry(&mut self, key: &[u8], value: &[u8]) -> Result<(), Self::Error> {
-        self.data.insert(Reverse(key.to_vec()), value.to_vec());
+        self.data.insert(key.to_vec(), value.to_vec());
         Ok(())
     }
 
     fn get_entry(&self, key: &[u8]) -> Result<Option<Self::V>, Self::Error> {
-        Ok(self.data.get(&Reverse(key.to_vec())).cloned())
+        Ok(self.data.get(key).cloned())
     }
 
     fn remove_entry(&mut self, key: &[u8]) -> Result<Option<Self::V>, Self::Error> {
-        Ok(self.data.remove(&Reverse(key.to_vec())))
+        Ok(self.data.remove(key))
     }
 
     fn scan_range(
         &self,
         range: Range<Self::K>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = Result<Self::K, Self::Error>>, Self::Error> {
-        let reverse_range = Reverse(range.end)..Reverse(range.start);
-        let iter = self.data.range(reverse_range);
-        Ok(iter.map(|(k, _v)| Ok(k.0.clone())))
+        let iter = direction.apply(self.data.range(range));
+        Ok(iter.map(|(k, _v)| Ok(k.clone())))
     }
 
     fn apply<U, E>(
@@ -110,10 +110,10 @@ impl Repository for MockAtomicStorage {
                 }
                 match op {
                     kivis::BatchOp::Insert { key, value } => {
-                        self.data.insert(Reverse(key), value);
+                        self.data.insert(key, value);
                     }
                     kivis::BatchOp::Delete { key } => {
-                        self.data.remove(&Reverse(key));
+                        self.data.remove(&key);
                     }
                 }
                 writes += 1;
@@ -320,3 +320,19 @@ fn test_atomicity_partial_write_rolled_back() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_insert_many_is_atomic() -> anyhow::Result<()> {
+    let mut storage = MockAtomicStorage::new();
+    storage.fail_after = 2;
+    let mut db = Database::<MockAtomicStorage, Manifest>::new(storage)?;
+
+    let records = [MockRecord(1, 'a'), MockRecord(2, 'b'), MockRecord(3, 'c')];
+    assert!(db.insert_many::<_, _, Vec<_>>(records).is_err());
+
+    // All records are committed by one `apply`, so none of them was written.
+    assert_eq!(db.get(&MockRecordKey(1))?, None);
+    assert_eq!(db.get(&MockRecordKey(2))?, None);
+
+    Ok(())
+}
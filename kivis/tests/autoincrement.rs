@@ -1,4 +1,4 @@
-use kivis::{Database, MemoryStorage, Record, manifest};
+use kivis::{Database, DatabaseError, Direction, Incrementable, MemoryStorage, Record, manifest};
 use serde::{Deserialize, Serialize};
 
 #[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -43,8 +43,117 @@ fn test_autoincrement_iter() -> anyhow::Result<()> {
     store.insert(another)?;
 
     let iter = store
-        .iter_keys(UserRecordKey(0)..UserRecordKey(3))?
+        .iter_keys(UserRecordKey(0)..UserRecordKey(3), Direction::Descending)?
         .collect::<Result<Vec<_>, _>>()?;
     assert_eq!(iter, vec![UserRecordKey(2), UserRecordKey(1)]);
     Ok(())
 }
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[autoincrement(u8)]
+struct Sensor {
+    name: String,
+}
+
+/// Ids that skip the reserved zero and grow by ten, leaving room for manual inserts.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+struct TicketId(u32);
+
+impl Incrementable for TicketId {
+    fn next_id(&self) -> Option<Self> {
+        self.0.checked_add(10).map(TicketId)
+    }
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[autoincrement(TicketId)]
+struct Ticket {
+    title: String,
+}
+
+manifest![Devices: Sensor, Ticket];
+
+#[test]
+fn test_autoincrement_type() -> anyhow::Result<()> {
+    let mut store = Database::<_, Devices>::new(MemoryStorage::default())?;
+
+    let first: SensorKey = store.put(Sensor {
+        name: "thermometer".to_string(),
+    })?;
+    let id: u8 = first.0;
+    assert_eq!(id, 1);
+
+    let ticket = store.put(Ticket {
+        title: "Replace battery".to_string(),
+    })?;
+    assert_eq!(ticket, TicketKey(TicketId(10)));
+    let ticket = store.put(Ticket {
+        title: "Calibrate".to_string(),
+    })?;
+    assert_eq!(ticket, TicketKey(TicketId(20)));
+    Ok(())
+}
+
+#[test]
+fn test_autoincrement_type_exhausted() -> anyhow::Result<()> {
+    let mut store = Database::<_, Devices>::new(MemoryStorage::default())?;
+    for _ in 0..u8::MAX {
+        store.put(Sensor {
+            name: "probe".to_string(),
+        })?;
+    }
+    assert_eq!(store.last_id::<SensorKey>()?, SensorKey(u8::MAX));
+
+    let result = store.put(Sensor {
+        name: "one too many".to_string(),
+    });
+    assert!(matches!(result, Err(DatabaseError::FailedToIncrement)));
+    Ok(())
+}
+
+#[test]
+fn test_sequence_survives_reopen() -> anyhow::Result<()> {
+    let mut store = Database::<_, Devices>::new(MemoryStorage::default())?;
+    assert_eq!(store.sequence::<SensorKey>()?, None);
+    let keys: Vec<SensorKey> = store.put_many(["a", "b", "c"].map(|name| Sensor {
+        name: name.to_string(),
+    }))?;
+    assert_eq!(keys, vec![SensorKey(1), SensorKey(2), SensorKey(3)]);
+    assert_eq!(store.sequence::<SensorKey>()?, Some(SensorKey(3)));
+    assert_eq!(store.sequence::<TicketKey>()?, None);
+
+    // Removing the newest record doesn't free its id, neither in memory nor after reopening.
+    store.remove(&SensorKey(3))?;
+    let key = store.put(Sensor {
+        name: "d".to_string(),
+    })?;
+    assert_eq!(key, SensorKey(4));
+    store.remove(&key)?;
+    assert_eq!(store.last_id::<SensorKey>()?, SensorKey(2));
+
+    let mut store = Database::<_, Devices>::new(store.dissolve())?;
+    assert_eq!(store.sequence::<SensorKey>()?, Some(SensorKey(4)));
+    let key = store.put(Sensor {
+        name: "e".to_string(),
+    })?;
+    assert_eq!(key, SensorKey(5));
+    Ok(())
+}
+
+#[test]
+fn test_sequence_written_on_commit() -> anyhow::Result<()> {
+    let mut store = Database::<_, Devices>::new(MemoryStorage::default())?;
+    let mut manifest = Devices::default();
+    let mut transaction = store.create_transaction();
+    transaction.put::<MemoryStorage, _>(
+        Sensor {
+            name: "pending".to_string(),
+        },
+        &mut manifest,
+    )?;
+    assert_eq!(store.sequence::<SensorKey>()?, None);
+
+    store.commit(transaction)?;
+    assert_eq!(store.sequence::<SensorKey>()?, Some(SensorKey(0)));
+    Ok(())
+}
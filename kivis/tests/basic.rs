@@ -1,4 +1,4 @@
-use kivis::{Database, MemoryStorage, Record, manifest};
+use kivis::{Database, Direction, MemoryStorage, Record, manifest};
 use serde::{Deserialize, Serialize};
 
 #[derive(Record, Default, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -42,8 +42,23 @@ fn test_iter() -> anyhow::Result<()> {
     assert_ne!(user_key, another_key);
 
     let iter = store
-        .iter_keys(UserRecordKey(0)..UserRecordKey(3))?
+        .iter_keys(UserRecordKey(0)..UserRecordKey(3), Direction::Descending)?
         .collect::<Result<Vec<_>, _>>()?;
     assert_eq!(iter, vec![another_key, user_key]);
     Ok(())
 }
+
+#[test]
+fn test_iter_bounds() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+
+    let first_key = store.put(UserRecord { data: vec![1] })?;
+    let second_key = store.put(UserRecord { data: vec![2] })?;
+
+    // The start of the range is included and the end excluded
+    let iter = store
+        .iter_keys(first_key.clone()..second_key, Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(iter, vec![first_key]);
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anyhow::Context;
+use kivis::{Database, Direction, Lexicographic, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Measurement {
+    value: u32,
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Station {
+    #[key]
+    id: u16,
+    #[index]
+    region: Lexicographic<String>,
+}
+
+manifest![Manifest: Measurement, Station];
+
+#[test]
+fn test_put_many() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+
+    let first = store.put(Measurement { value: 0 })?;
+    assert_eq!(first, MeasurementKey(1));
+    let keys: Vec<_> = store.put_many((1..=3).map(|value| Measurement { value }))?;
+    assert_eq!(
+        keys,
+        vec![MeasurementKey(2), MeasurementKey(3), MeasurementKey(4)]
+    );
+
+    // The manifest counter continues after the batch.
+    let next = store.put(Measurement { value: 4 })?;
+    assert_eq!(next, MeasurementKey(5));
+
+    let values = store
+        .iter_all_records::<Measurement>(Direction::Ascending)?
+        .map(|record| record.map(|(_, measurement)| measurement.value))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_insert_many() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    let station = |id, region: &str| Station {
+        id,
+        region: region.into(),
+    };
+
+    store.insert(station(1, "north"))?;
+    let keys: Vec<_> =
+        store.insert_many([station(1, "south"), station(2, "north"), station(2, "east")])?;
+    assert_eq!(keys, vec![StationKey(1), StationKey(2), StationKey(2)]);
+
+    assert_eq!(
+        store.get(&StationKey(1))?.context("Missing")?.region,
+        "south"
+    );
+    assert_eq!(
+        store.get(&StationKey(2))?.context("Missing")?.region,
+        "east"
+    );
+
+    // Index entries replaced within the batch, or from before it, are gone.
+    let north = store
+        .iter_by_index_exact(&StationRegionIndex("north".into()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(north.is_empty());
+    assert_eq!(store.count_by_index::<StationRegionIndex>(..)?, 2);
+    Ok(())
+}
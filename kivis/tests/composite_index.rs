@@ -0,0 +1,78 @@
+use kivis::{Database, Direction, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[index(name = "by_status_date", fields(status, created_at))]
+#[index(fields(owner, priority), unique)]
+struct Task {
+    #[index]
+    owner: u8,
+    status: u8,
+    created_at: u32,
+    priority: u8,
+}
+
+manifest![Manifest: Task];
+
+fn setup() -> anyhow::Result<Database<MemoryStorage, Manifest>> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    for (owner, status, created_at) in [(1, 2, 30), (2, 1, 20), (3, 2, 10), (4, 1, 40)] {
+        store.put(Task {
+            owner,
+            status,
+            created_at,
+            priority: 0,
+        })?;
+    }
+    Ok(store)
+}
+
+#[test]
+fn test_composite_index_range() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let keys = store
+        .iter_by_index::<TaskByStatusDateIndex>(.., Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![TaskKey(2), TaskKey(4), TaskKey(3), TaskKey(1)]);
+
+    let keys = store
+        .iter_by_index(
+            TaskByStatusDateIndex(1, 30)..=TaskByStatusDateIndex(2, 10),
+            Direction::Ascending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![TaskKey(4), TaskKey(3)]);
+    Ok(())
+}
+
+#[test]
+fn test_composite_index_prefix() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let keys = store
+        .iter_by_index_with_prefix::<TaskByStatusDateIndex, _>(&(2u8,), Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![TaskKey(1), TaskKey(3)]);
+
+    let keys = store
+        .iter_by_index_with_prefix::<TaskByStatusDateIndex, _>(&(3u8,), Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(keys.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_composite_index_default_name() -> anyhow::Result<()> {
+    let mut store = setup()?;
+
+    // The field-level index keeps the first discriminator.
+    let keys = store
+        .iter_by_index_exact(&TaskOwnerIndex(3))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![TaskKey(3)]);
+
+    let task = store.get_by_unique(&TaskOwnerPriorityIndex(4, 0))?;
+    assert_eq!(task.map(|task| task.status), Some(1));
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use kivis::{Database, Direction, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[index(name = "tag_count", with = Self::tag_count, ty = u8)]
+struct Contact {
+    #[index]
+    #[index(name = "normalized_email", with = normalize_email, ty = String, unique)]
+    email: String,
+    #[index(with = Vec::len, ty = usize)]
+    tags: Vec<String>,
+}
+
+impl Contact {
+    fn tag_count(&self) -> u8 {
+        u8::try_from(self.tags.len()).unwrap_or(u8::MAX)
+    }
+}
+
+manifest![Manifest: Contact];
+
+fn contact(email: &str, tags: &[&str]) -> Contact {
+    Contact {
+        email: email.to_string(),
+        tags: tags.iter().map(ToString::to_string).collect(),
+    }
+}
+
+fn setup() -> anyhow::Result<Database<MemoryStorage, Manifest>> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    store.put(contact("Alice@Example.com", &["friend", "work"]))?;
+    store.put(contact(" bob@example.com", &[]))?;
+    store.put(contact("carol@example.com", &["work"]))?;
+    Ok(store)
+}
+
+#[test]
+fn test_field_computed_index() -> anyhow::Result<()> {
+    let mut store = setup()?;
+
+    let alice = store.get_by_unique(&ContactNormalizedEmailIndex(
+        "alice@example.com".to_string(),
+    ))?;
+    assert_eq!(
+        alice,
+        Some(contact("Alice@Example.com", &["friend", "work"]))
+    );
+
+    // The plain index on the same field keeps the original value.
+    let keys = store
+        .iter_by_index_exact(&ContactEmailIndex(" bob@example.com".to_string()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![ContactKey(2)]);
+
+    let keys = store
+        .iter_by_index(
+            ContactTagsIndex(1)..ContactTagsIndex(3),
+            Direction::Ascending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![ContactKey(3), ContactKey(1)]);
+    Ok(())
+}
+
+#[test]
+fn test_record_computed_index() -> anyhow::Result<()> {
+    let mut store = setup()?;
+
+    let keys = store
+        .iter_by_index::<ContactTagCountIndex>(.., Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![ContactKey(2), ContactKey(3), ContactKey(1)]);
+
+    // Updating the record moves its computed entries.
+    store.update(&ContactKey(2), |contact: &mut Contact| {
+        contact.tags = vec!["a".into(), "b".into(), "c".into()];
+    })?;
+    let keys = store
+        .iter_by_index_exact(&ContactTagCountIndex(3))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![ContactKey(2)]);
+    assert_eq!(store.count_by_index(..ContactTagCountIndex(1))?, 0);
+    Ok(())
+}
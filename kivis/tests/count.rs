@@ -0,0 +1,62 @@
+use kivis::{Database, Lexicographic, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct User {
+    #[index]
+    city: Lexicographic<String>,
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct City {
+    #[key]
+    name: String,
+}
+
+manifest![Manifest: User, City];
+
+fn setup() -> anyhow::Result<Database<MemoryStorage, Manifest>> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    for city in ["Oslo", "Oslo", "Paris", "Rome"] {
+        store.put(User { city: city.into() })?;
+    }
+    store.insert(City {
+        name: "Oslo".to_string(),
+    })?;
+    Ok(store)
+}
+
+#[test]
+fn test_contains() -> anyhow::Result<()> {
+    let mut store = setup()?;
+
+    assert!(store.contains(&UserKey(1))?);
+    assert!(!store.contains(&UserKey(5))?);
+    assert!(store.contains(&CityKey("Oslo".to_string()))?);
+    assert!(!store.contains(&CityKey("Rome".to_string()))?);
+
+    store.remove(&UserKey(1))?;
+    assert!(!store.contains(&UserKey(1))?);
+    Ok(())
+}
+
+#[test]
+fn test_count() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    assert_eq!(store.count::<User>(..)?, 4);
+    assert_eq!(store.count::<User>(UserKey(2)..=UserKey(3))?, 2);
+    assert_eq!(store.count::<City>(..)?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_count_by_index() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let oslo = UserCityIndex("Oslo".into());
+    assert_eq!(store.count_by_index::<UserCityIndex>(&oslo..=&oslo)?, 2);
+    assert_eq!(store.count_by_index(UserCityIndex("P".into())..)?, 2);
+    assert_eq!(store.count_by_index::<UserCityIndex>(..)?, 4);
+    Ok(())
+}
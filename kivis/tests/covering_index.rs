@@ -0,0 +1,196 @@
+use bincode::error::{DecodeError, EncodeError};
+use kivis::{
+    BufferOverflowOr, Database, DatabaseError, Direction, MemoryStorage, OrderedUnifier, Record,
+    Storage, Unifier, manifest,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[index(name = "by_shelf", fields(shelf, position), include(name))]
+struct Product {
+    #[index(include(name, price))]
+    category: u8,
+    #[index(unique, include(price))]
+    sku: u32,
+    name: String,
+    price: f32,
+    shelf: u8,
+    position: u8,
+    description: String,
+}
+
+manifest![Manifest: Product];
+
+fn product(category: u8, sku: u32, name: &str, price: f32) -> Product {
+    Product {
+        category,
+        sku,
+        name: name.to_string(),
+        price,
+        shelf: category,
+        position: u8::try_from(sku % 100).unwrap_or_default(),
+        description: "A rather long description that list views never show".to_string(),
+    }
+}
+
+fn setup() -> anyhow::Result<Database<MemoryStorage, Manifest>> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    store.put(product(1, 101, "Kettle", 25.0))?;
+    store.put(product(2, 202, "Lamp", 40.0))?;
+    store.put(product(1, 103, "Toaster", 30.0))?;
+    Ok(store)
+}
+
+#[test]
+fn test_iter_projection_by_index() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let projections = store
+        .iter_projection_by_index(
+            ProductCategoryIndex(1)..=ProductCategoryIndex(1),
+            Direction::Ascending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        projections,
+        vec![
+            (
+                ProductKey(1),
+                ProductCategoryProjection {
+                    name: "Kettle".to_string(),
+                    price: 25.0,
+                }
+            ),
+            (
+                ProductKey(3),
+                ProductCategoryProjection {
+                    name: "Toaster".to_string(),
+                    price: 30.0,
+                }
+            ),
+        ]
+    );
+
+    let names = store
+        .iter_projection_by_index::<ProductByShelfIndex>(.., Direction::Descending)?
+        .map(|entry| entry.map(|(_, projection)| projection.name))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(names, vec!["Lamp", "Toaster", "Kettle"]);
+    Ok(())
+}
+
+#[test]
+fn test_covering_index_still_yields_keys() -> anyhow::Result<()> {
+    let mut store = setup()?;
+
+    let keys = store
+        .iter_by_index_exact(&ProductCategoryIndex(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![ProductKey(3), ProductKey(1)]);
+
+    let lamp = store.get_by_unique(&ProductSkuIndex(202))?;
+    assert_eq!(lamp.map(|lamp| lamp.name), Some("Lamp".to_string()));
+    Ok(())
+}
+
+#[test]
+fn test_projection_follows_updates() -> anyhow::Result<()> {
+    let mut store = setup()?;
+
+    store.update(&ProductKey(2), |product: &mut Product| product.price = 35.0)?;
+    store.update(&ProductKey(1), |product: &mut Product| {
+        product.description = "Whistles".to_string();
+    })?;
+
+    let prices = store
+        .iter_projection_by_index::<ProductSkuIndex>(.., Direction::Ascending)?
+        .map(|entry| entry.map(|(key, projection)| (key, projection.price)))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        prices,
+        vec![
+            (ProductKey(1), 25.0),
+            (ProductKey(3), 30.0),
+            (ProductKey(2), 35.0)
+        ]
+    );
+
+    // Unique entries storing a projection still recognize their owner.
+    let mut renamed = product(2, 202, "Desk lamp", 35.0);
+    renamed.shelf = 9;
+    store.update(&ProductKey(2), |product: &mut Product| {
+        *product = renamed.clone()
+    })?;
+    let result = store.put(product(3, 202, "Copy", 1.0));
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+    Ok(())
+}
+
+/// A value unifier rejecting data left over after the value.
+#[derive(Debug, Clone, Copy, Default)]
+struct StrictUnifier;
+
+impl Unifier for StrictUnifier {
+    type D = Vec<u8>;
+    type SerError = EncodeError;
+    type DeError = DecodeError;
+
+    fn serialize(
+        &self,
+        buffer: &mut Self::D,
+        data: &impl Serialize,
+    ) -> Result<(usize, usize), BufferOverflowOr<Self::SerError>> {
+        let start = buffer.len();
+        buffer.extend(bincode::serde::encode_to_vec(
+            data,
+            bincode::config::standard(),
+        )?);
+        Ok((start, buffer.len()))
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, data: &Self::D) -> Result<T, Self::DeError> {
+        let (value, read) = bincode::serde::decode_from_slice(data, bincode::config::standard())?;
+        if read != data.len() {
+            return Err(DecodeError::Other("trailing data"));
+        }
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Default)]
+struct StrictStorage(MemoryStorage);
+
+impl Storage for StrictStorage {
+    type Repo = MemoryStorage;
+    type Unifiers = (OrderedUnifier, StrictUnifier);
+
+    fn repository(&self) -> &Self::Repo {
+        &self.0
+    }
+
+    fn repository_mut(&mut self) -> &mut Self::Repo {
+        &mut self.0
+    }
+}
+
+#[test]
+fn test_keys_read_by_strict_unifiers() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(StrictStorage::default())?;
+    store.put(product(1, 101, "Kettle", 25.0))?;
+    store.put(product(1, 103, "Toaster", 30.0))?;
+
+    let keys = store
+        .iter_by_index(ProductCategoryIndex(1).., Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![ProductKey(1), ProductKey(2)]);
+
+    let toaster = store.get_by_unique(&ProductSkuIndex(103))?;
+    assert_eq!(
+        toaster.map(|toaster| toaster.name),
+        Some("Toaster".to_string())
+    );
+
+    let result = store.put(product(2, 103, "Copy", 1.0));
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+    Ok(())
+}
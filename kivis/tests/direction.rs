@@ -0,0 +1,56 @@
+use kivis::{Database, Direction, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Event {
+    #[index]
+    priority: u8,
+}
+
+manifest![Manifest: Event];
+
+fn setup() -> anyhow::Result<Database<MemoryStorage, Manifest>> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    for priority in [3, 1, 2] {
+        store.put(Event { priority })?;
+    }
+    Ok(store)
+}
+
+#[test]
+fn test_iter_keys_direction() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let ascending = store
+        .iter_all_keys::<EventKey>(Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(ascending, vec![EventKey(1), EventKey(2), EventKey(3)]);
+
+    let descending = store
+        .iter_keys(EventKey(1)..EventKey(3), Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(descending, vec![EventKey(2), EventKey(1)]);
+
+    let records = store
+        .iter_all_records::<Event>(Direction::Ascending)?
+        .map(|record| record.map(|(_, event)| event.priority))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(records, vec![3, 1, 2]);
+    Ok(())
+}
+
+#[test]
+fn test_iter_by_index_direction() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let ascending = store
+        .iter_by_index::<EventPriorityIndex>(.., Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(ascending, vec![EventKey(2), EventKey(3), EventKey(1)]);
+
+    let descending = store
+        .iter_by_index::<EventPriorityIndex>(.., Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(descending, vec![EventKey(1), EventKey(3), EventKey(2)]);
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use kivis::{
+    BufferOverflowOr, Database, DatabaseEntry, DatabaseError, Direction, MemoryStorage, Migrate,
+    OrderedUnifier, Record, Storage, Unifier, Versioned, manifest,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+mod before {
+    use super::*;
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[record(envelope)]
+    pub struct Note {
+        #[key]
+        pub id: u32,
+        #[index]
+        pub title: String,
+    }
+
+    manifest![Notes: Note];
+}
+
+mod after {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct NoteV1 {
+        pub id: u32,
+        pub title: String,
+    }
+
+    impl Versioned for NoteV1 {
+        const VERSION: u32 = 1;
+    }
+
+    impl Migrate for NoteV1 {
+        type From = Self;
+
+        fn migrate(previous: Self) -> Self {
+            previous
+        }
+    }
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[record(version = 2, envelope)]
+    pub struct Note {
+        #[key]
+        pub id: u32,
+        #[index]
+        pub title: String,
+        pub pinned: bool,
+    }
+
+    impl Migrate for Note {
+        type From = NoteV1;
+
+        fn migrate(previous: NoteV1) -> Self {
+            Self {
+                id: previous.id,
+                title: previous.title,
+                pinned: false,
+            }
+        }
+    }
+
+    manifest![Notes: Note];
+}
+
+mod strict {
+    use super::*;
+
+    // Reads only values written at version 2.
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[record(version = 2, envelope)]
+    pub struct Note {
+        #[key]
+        pub id: u32,
+        #[index]
+        pub title: String,
+        pub pinned: bool,
+    }
+
+    impl Migrate for Note {
+        type From = Self;
+
+        fn migrate(previous: Self) -> Self {
+            previous
+        }
+    }
+
+    manifest![Notes: Note];
+}
+
+fn stored_notes() -> anyhow::Result<MemoryStorage> {
+    let mut store = Database::<_, before::Notes>::new(MemoryStorage::default())?;
+    for (id, title) in [(1, "groceries"), (2, "ideas")] {
+        store.insert(before::Note {
+            id,
+            title: title.to_string(),
+        })?;
+    }
+    Ok(store.dissolve())
+}
+
+#[test]
+fn test_envelope_fingerprint() {
+    assert_eq!(
+        after::Note::FINGERPRINT,
+        "fields(u32, String, bool); key: fields(u32); index 0: String; envelope"
+    );
+}
+
+#[test]
+fn test_older_values_upgraded_on_read() -> anyhow::Result<()> {
+    let mut store = Database::<_, after::Notes>::new(stored_notes()?)?;
+    store.insert(after::Note {
+        id: 3,
+        title: "todo".to_string(),
+        pinned: true,
+    })?;
+
+    let note = store.get(&after::NoteKey(2))?;
+    assert_eq!(
+        note,
+        Some(after::Note {
+            id: 2,
+            title: "ideas".to_string(),
+            pinned: false,
+        })
+    );
+    let pinned = store
+        .iter_all_records::<after::Note>(Direction::Ascending)?
+        .map(|entry| entry.map(|(_, note)| note.pinned))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(pinned, vec![false, false, true]);
+
+    // Reading doesn't rewrite the older values.
+    let mut store = Database::<_, strict::Notes>::new(store.dissolve())?;
+    assert!(store.get(&strict::NoteKey(3))?.is_some());
+    let result = store.get(&strict::NoteKey(2));
+    assert!(
+        matches!(
+            result,
+            Err(DatabaseError::UnsupportedVersion {
+                scope: 0,
+                version: 1
+            })
+        ),
+        "expected an unsupported version, got {result:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_upgraded_record_written_back() -> anyhow::Result<()> {
+    let mut store = Database::<_, after::Notes>::new(stored_notes()?)?;
+    let note = store.get_upgraded::<after::Note>(&after::NoteKey(1))?;
+    assert_eq!(note.map(|note| note.title), Some("groceries".to_string()));
+
+    let mut store = Database::<_, strict::Notes>::new(store.dissolve())?;
+    assert!(store.get(&strict::NoteKey(1))?.is_some());
+    assert!(store.get(&strict::NoteKey(2)).is_err());
+
+    // The index entry of the rewritten record is kept.
+    let keys = store
+        .iter_by_index_exact(&strict::NoteTitleIndex("groceries".to_string()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![strict::NoteKey(1)]);
+    Ok(())
+}
+
+#[test]
+fn test_migrate_enveloped_values() -> anyhow::Result<()> {
+    let mut store = Database::<_, after::Notes>::new(stored_notes()?)?;
+    store.get_upgraded::<after::Note>(&after::NoteKey(1))?;
+    assert_eq!(store.migrate()?, 2);
+
+    let mut store = Database::<_, strict::Notes>::new(store.dissolve())?;
+    let titles = store
+        .iter_all_records::<strict::Note>(Direction::Ascending)?
+        .map(|entry| entry.map(|(_, note)| note.title))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(titles, vec!["groceries", "ideas"]);
+    assert_eq!(store.migrate()?, 0);
+    Ok(())
+}
+
+/// Stores values as JSON objects, whose fields are read by name.
+#[derive(Debug, Clone, Copy, Default)]
+struct JsonUnifier;
+
+impl Unifier for JsonUnifier {
+    type D = Vec<u8>;
+    type SerError = serde_json::Error;
+    type DeError = serde_json::Error;
+
+    fn serialize(
+        &self,
+        buffer: &mut Self::D,
+        data: &impl Serialize,
+    ) -> Result<(usize, usize), BufferOverflowOr<Self::SerError>> {
+        let start = buffer.len();
+        serde_json::to_writer(&mut *buffer, data)?;
+        Ok((start, buffer.len()))
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, data: &Self::D) -> Result<T, Self::DeError> {
+        serde_json::from_slice(data)
+    }
+}
+
+#[derive(Debug, Default)]
+struct JsonStorage(MemoryStorage);
+
+impl Storage for JsonStorage {
+    type Repo = MemoryStorage;
+    type Unifiers = (OrderedUnifier, JsonUnifier);
+
+    fn repository(&self) -> &Self::Repo {
+        &self.0
+    }
+
+    fn repository_mut(&mut self) -> &mut Self::Repo {
+        &mut self.0
+    }
+}
+
+#[test]
+fn test_envelopes_read_as_maps() -> anyhow::Result<()> {
+    let mut store = Database::<_, before::Notes>::new(JsonStorage::default())?;
+    store.insert(before::Note {
+        id: 1,
+        title: "groceries".to_string(),
+    })?;
+
+    let mut store = Database::<_, after::Notes>::new(store.dissolve())?;
+    let note = store.get(&after::NoteKey(1))?;
+    assert_eq!(note.map(|note| note.pinned), Some(false));
+    assert_eq!(store.migrate()?, 1);
+
+    let mut store = Database::<_, strict::Notes>::new(store.dissolve())?;
+    assert!(store.get(&strict::NoteKey(1))?.is_some());
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use kivis::{Database, Lexicographic, MemoryStorage, Record, manifest};
+use kivis::{Database, Direction, Lexicographic, MemoryStorage, Record, manifest};
 
 #[derive(
     Record, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
@@ -78,6 +78,7 @@ fn test_iter_by_flight_number() -> anyhow::Result<()> {
     let aa_flights = store
         .iter_by_index(
             FlightFlightNumberIndex("AA200".into())..FlightFlightNumberIndex("AA400".into()),
+            Direction::Descending,
         )?
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -14,7 +14,10 @@
  * - Database.insert() method for records with derived keys
  */
 
-use kivis::{Database, DatabaseEntry, DeriveKey, MemoryStorage, RecordKey, manifest};
+use kivis::{
+    Database, DatabaseEntry, DeriveKey, FieldDescriptor, KeyDescriptor, MemoryStorage,
+    RecordDescriptor, RecordKey, RecordSchema, Scope, manifest,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -46,6 +49,24 @@ impl DeriveKey for ContentRecord {
 impl DatabaseEntry for ContentRecord {
     type Key = ContentHashKey;
 }
+impl RecordSchema for ContentRecord {
+    const SCHEMA: RecordDescriptor = RecordDescriptor {
+        name: "ContentRecord",
+        scope: Self::SCOPE,
+        fields: &[
+            FieldDescriptor {
+                name: "data",
+                type_name: "String",
+            },
+            FieldDescriptor {
+                name: "value",
+                type_name: "u32",
+            },
+        ],
+        key: KeyDescriptor::Derived(&["u64"]),
+        indexes: &[],
+    };
+}
 
 manifest![Manifest: ContentRecord];
 
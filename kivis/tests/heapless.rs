@@ -13,6 +13,26 @@ impl AsRef<Bytes> for Bytes {
     }
 }
 
+impl PartialEq for Bytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.0[..self.1] == other.0[..other.1]
+    }
+}
+
+impl Eq for Bytes {}
+
+impl PartialOrd for Bytes {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Bytes {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0[..self.1].cmp(&other.0[..other.1])
+    }
+}
+
 impl From<&Bytes> for Bytes {
     fn from(slice: &Bytes) -> Self {
         Bytes(slice.0, slice.1)
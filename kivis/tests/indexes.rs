@@ -1,6 +1,6 @@
 use anyhow::Context;
 use bincode::serde::encode_to_vec;
-use kivis::{Database, DatabaseEntry, Index, MemoryStorage, Record, manifest};
+use kivis::{Database, DatabaseEntry, Direction, Index, MemoryStorage, Record, manifest};
 
 #[derive(
     Record, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
@@ -102,7 +102,8 @@ fn test_index() -> anyhow::Result<()> {
     let retrieved = store.get(&user_key)?.context("Missing")?;
     assert_eq!(retrieved, user);
 
-    assert_eq!(store.dissolve().len(), 2);
+    // The record, its name index entry, the sequence of the table and the schemas and index counts of both tables.
+    assert_eq!(store.dissolve().len(), 7);
     Ok(())
 }
 
@@ -118,7 +119,7 @@ fn test_keys_iter() -> anyhow::Result<()> {
     store.put(pet)?;
 
     let retrieved = store
-        .iter_keys(PetKey(0)..PetKey(u64::MAX))?
+        .iter_keys(PetKey(0)..PetKey(u64::MAX), Direction::Descending)?
         .next()
         .context("Missing")??;
 
@@ -138,7 +139,10 @@ fn test_iter_index() -> anyhow::Result<()> {
     store.put(user)?;
 
     let retrieved = store
-        .iter_by_index(UserNameIndex("A".to_string())..UserNameIndex("Bob".to_string()))?
+        .iter_by_index(
+            UserNameIndex("A".to_string())..UserNameIndex("Bob".to_string()),
+            Direction::Descending,
+        )?
         .next()
         .transpose()?;
     let retrieved = retrieved.context("Missing")?;
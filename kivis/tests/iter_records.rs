@@ -0,0 +1,85 @@
+use kivis::{Database, Direction, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Book {
+    title: String,
+    pages: u16,
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Shelf {
+    #[key]
+    number: u8,
+    capacity: u16,
+}
+
+manifest![Library: Book, Shelf];
+
+#[test]
+fn test_iter_records() -> anyhow::Result<()> {
+    let mut store = Database::<_, Library>::new(MemoryStorage::default())?;
+
+    let books = [("Dune", 412), ("Emma", 474), ("Ulysses", 730)].map(|(title, pages)| Book {
+        title: title.to_string(),
+        pages,
+    });
+    let mut keys = Vec::new();
+    for book in &books {
+        keys.push(store.put(book.clone())?);
+    }
+
+    let records = store
+        .iter_records::<Book>(BookKey(2)..BookKey(4), Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        records,
+        vec![
+            (keys[2].clone(), books[2].clone()),
+            (keys[1].clone(), books[1].clone())
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_iter_all_records() -> anyhow::Result<()> {
+    let mut store = Database::<_, Library>::new(MemoryStorage::default())?;
+
+    store.put(Book {
+        title: "Dune".to_string(),
+        pages: 412,
+    })?;
+    let shelves = [
+        Shelf {
+            number: 1,
+            capacity: 40,
+        },
+        Shelf {
+            number: 2,
+            capacity: 60,
+        },
+    ];
+    for shelf in &shelves {
+        store.insert(shelf.clone())?;
+    }
+
+    // Only records of the requested table are returned.
+    let records = store
+        .iter_all_records::<Shelf>(Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        records,
+        vec![
+            (ShelfKey(2), shelves[1].clone()),
+            (ShelfKey(1), shelves[0].clone())
+        ]
+    );
+
+    store.remove(&ShelfKey(1))?;
+    let records = store
+        .iter_all_records::<Shelf>(Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(records, vec![(ShelfKey(2), shelves[1].clone())]);
+    Ok(())
+}
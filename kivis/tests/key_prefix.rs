@@ -0,0 +1,89 @@
+use kivis::{Database, Direction, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Membership {
+    #[key]
+    tenant: u32,
+    #[key]
+    team: String,
+    #[key]
+    user: u32,
+    role: String,
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Tenant {
+    #[key]
+    id: u32,
+}
+
+manifest![Manifest: Membership, Tenant];
+
+fn membership(tenant: u32, team: &str, user: u32) -> Membership {
+    Membership {
+        tenant,
+        team: team.to_string(),
+        user,
+        role: "member".to_string(),
+    }
+}
+
+fn setup() -> anyhow::Result<Database<MemoryStorage, Manifest>> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    for member in [
+        membership(1, "core", 10),
+        membership(1, "core", 11),
+        membership(1, "docs", 12),
+        membership(2, "core", 20),
+        membership(3, "ops", 30),
+    ] {
+        store.insert(member)?;
+    }
+    // Records of another type must never leak into the scans.
+    store.insert(Tenant { id: 1 })?;
+    Ok(store)
+}
+
+#[test]
+fn test_iter_keys_with_single_field_prefix() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let keys = store
+        .iter_keys_with_prefix::<MembershipKey, _>(&(1u32,), Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        keys,
+        vec![
+            MembershipKey(1, "core".to_string(), 10),
+            MembershipKey(1, "core".to_string(), 11),
+            MembershipKey(1, "docs".to_string(), 12),
+        ]
+    );
+
+    let keys = store
+        .iter_keys_with_prefix::<MembershipKey, _>(&(4u32,), Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(keys.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_iter_keys_with_two_field_prefix() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let keys = store
+        .iter_keys_with_prefix::<MembershipKey, _>(
+            &(1u32, "core".to_string()),
+            Direction::Descending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        keys,
+        vec![
+            MembershipKey(1, "core".to_string(), 11),
+            MembershipKey(1, "core".to_string(), 10),
+        ]
+    );
+    Ok(())
+}
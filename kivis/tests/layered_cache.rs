@@ -62,7 +62,7 @@ impl DeriveKey for NamedRecord {
     }
 }
 
-manifest![NamedManifest + TestCache: NamedRecord];
+manifest![NamedManifest + TestCache: NamedRecord = 5; retired = [0]];
 
 #[test]
 fn test_layered_cache_architecture() -> anyhow::Result<()> {
@@ -1,4 +1,4 @@
-use kivis::{Record, Scope, manifest};
+use kivis::{Database, Direction, MemoryStorage, Record, Scope, manifest};
 use serde::{Deserialize, Serialize};
 
 // Define some test structs
@@ -79,6 +79,91 @@ fn test_manifest_macro_empty_struct() {
     assert_eq!(EmptyManifest::members(), vec![0]);
 }
 
+#[test]
+fn test_manifest_macro_explicit_scopes() {
+    #[derive(Debug, Clone, Record, Deserialize, Serialize)]
+    struct Listed {
+        _id: u64,
+    }
+    #[derive(Debug, Clone, Record, Deserialize, Serialize)]
+    #[record(scope = 9)]
+    struct Declared {
+        _id: u64,
+    }
+    #[derive(Debug, Clone, Record, Deserialize, Serialize)]
+    #[record(scope = 4)]
+    struct Both {
+        _id: u64,
+    }
+    #[derive(Debug, Clone, Record, Deserialize, Serialize)]
+    struct Positional {
+        _id: u64,
+    }
+    manifest![Explicit: Listed = 7, Declared, Both = 4, Positional; retired = [2, 5,]];
+    assert_eq!(Listed::SCOPE, 7);
+    assert_eq!(Declared::SCOPE, 9);
+    assert_eq!(Both::SCOPE, 4);
+    assert_eq!(Positional::SCOPE, 3);
+    assert_eq!(Explicit::members(), vec![7, 9, 4, 3]);
+    assert_eq!(Explicit::retired(), vec![2, 5]);
+}
+
+mod before {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Record, Deserialize, Serialize)]
+    pub struct Note {
+        pub text: String,
+    }
+    #[derive(Debug, Clone, PartialEq, Record, Deserialize, Serialize)]
+    pub struct Label {
+        pub name: String,
+    }
+    manifest![Notes: Note = 1, Label = 2];
+}
+
+mod after {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Record, Deserialize, Serialize)]
+    pub struct Folder {
+        pub path: String,
+    }
+    #[derive(Debug, Clone, PartialEq, Record, Deserialize, Serialize)]
+    pub struct Note {
+        pub text: String,
+    }
+    // `Label` was removed and `Folder` added in front, the notes keep their table.
+    manifest![Notes: Folder = 3, Note = 1; retired = [2]];
+}
+
+#[test]
+fn test_explicit_scopes_survive_reordering() -> anyhow::Result<()> {
+    let mut store = Database::<_, before::Notes>::new(MemoryStorage::default())?;
+    let note = store.put(before::Note {
+        text: "milk".to_string(),
+    })?;
+    store.put(before::Label {
+        name: "shopping".to_string(),
+    })?;
+
+    let mut store = Database::<_, after::Notes>::new(store.dissolve())?;
+    let note = store.get(&after::NoteKey(note.0))?;
+    assert_eq!(
+        note,
+        Some(after::Note {
+            text: "milk".to_string()
+        })
+    );
+    assert_eq!(
+        store
+            .iter_all_keys::<after::FolderKey>(Direction::Ascending)?
+            .count(),
+        0
+    );
+    Ok(())
+}
+
 // #[test]
 // fn test_manifest_macro_large_list() {
 //     struct T0;
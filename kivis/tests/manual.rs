@@ -8,8 +8,9 @@ use std::{collections::BTreeMap, ops::Range};
 use thiserror::Error;
 
 use kivis::{
-    BufferOverflowError, BufferOverflowOr, Cache, Database, DatabaseEntry, DeriveKey,
-    Incrementable, Index, RecordKey, RecordOps, Repository, Scope, Storage, UnifierPair,
+    BufferOverflowError, BufferOverflowOr, Cache, Database, DatabaseEntry, DeriveKey, Direction,
+    Incrementable, Index, OrderedUnifier, RecordKey, RecordOps, Repository, Scope, Storage,
+    UnifierPair,
 };
 
 // Define a record type for an User.
@@ -102,17 +103,22 @@ struct Manifest {
 #[derive(Clone, Copy)]
 #[allow(dead_code)]
 enum ManifestRecord<'a> {
-    User(&'a UserKey, &'a User),
-    Pet(&'a PetKey, &'a Pet),
+    User(&'a UserKey, &'a User, Option<&'a User>),
+    Pet(&'a PetKey, &'a Pet, Option<&'a Pet>),
 }
 impl<'a> From<&'a (UserKey, User)> for ManifestRecord<'a> {
     fn from(pair: &'a (UserKey, User)) -> Self {
-        Self::User(&pair.0, &pair.1)
+        Self::User(&pair.0, &pair.1, None)
+    }
+}
+impl<'a> From<&'a (UserKey, User, User)> for ManifestRecord<'a> {
+    fn from(triple: &'a (UserKey, User, User)) -> Self {
+        Self::User(&triple.0, &triple.1, Some(&triple.2))
     }
 }
 impl<'a> From<&'a (PetKey, Pet)> for ManifestRecord<'a> {
     fn from(pair: &'a (PetKey, Pet)) -> Self {
-        Self::Pet(&pair.0, &pair.1)
+        Self::Pet(&pair.0, &pair.1, None)
     }
 }
 
@@ -155,7 +161,7 @@ impl<U: UnifierPair> kivis::Manifest<U> for Manifest {
     {
         *self = Self {
             last_user: None,
-            last_pet: Some(db.last_id::<PetKey>()?),
+            last_pet: Some(db.sequence::<PetKey>()?.unwrap_or_default()),
         };
         Ok(())
     }
@@ -169,12 +175,12 @@ impl<U: UnifierPair> kivis::Manifest<U> for Manifest {
         U: 'a,
     {
         match record {
-            ManifestRecord::User(key, val) => {
-                ManifestOps::User(kivis::build_record_ops(op, val, key, unifiers))
-            }
-            ManifestRecord::Pet(key, val) => {
-                ManifestOps::Pet(kivis::build_record_ops(op, val, key, unifiers))
-            }
+            ManifestRecord::User(key, val, previous) => ManifestOps::User(
+                kivis::build_record_ops(op, val, key, unifiers).with_previous(previous),
+            ),
+            ManifestRecord::Pet(key, val, previous) => ManifestOps::Pet(
+                kivis::build_record_ops(op, val, key, unifiers).with_previous(previous),
+            ),
         }
     }
 }
@@ -206,7 +212,7 @@ enum NoError {
 
 impl Storage for ManualStorage {
     type Repo = Self;
-    type Unifiers = (Configuration, Configuration);
+    type Unifiers = (OrderedUnifier, Configuration);
     fn repository(&self) -> &Self::Repo {
         self
     }
@@ -236,8 +242,9 @@ impl Repository for ManualStorage {
     fn scan_range(
         &self,
         range: Range<Vec<u8>>,
+        direction: Direction,
     ) -> Result<impl Iterator<Item = Result<Vec<u8>, Self::Error>>, Self::Error> {
-        let iter = self.data.range(range);
+        let iter = direction.apply(self.data.range(range));
         Ok(iter.map(|(k, _v)| Ok(k.clone())))
     }
 }
@@ -345,7 +352,7 @@ fn test_iter() -> anyhow::Result<()> {
     let pet_key = database.put(pet)?;
 
     let retrieved = database
-        .iter_keys(PetKey(1)..PetKey(u64::MAX))?
+        .iter_keys(PetKey(1)..PetKey(u64::MAX), Direction::Descending)?
         .next()
         .context("Missing")??;
 
@@ -365,21 +372,30 @@ fn test_iter_index() -> anyhow::Result<()> {
 
     // Before inserting the user.
     let retrieved = database
-        .iter_by_index(UserNameIndex("A".to_string())..UserNameIndex("Bob".to_string()))?
+        .iter_by_index(
+            UserNameIndex("A".to_string())..UserNameIndex("Bob".to_string()),
+            Direction::Descending,
+        )?
         .collect::<Vec<_>>();
     assert!(retrieved.is_empty());
 
     // After inserting the user.
     database.insert(user.clone())?;
     let retrieved = database
-        .iter_by_index(UserNameIndex("A".to_string())..UserNameIndex("Bob".to_string()))?
+        .iter_by_index(
+            UserNameIndex("A".to_string())..UserNameIndex("Bob".to_string()),
+            Direction::Descending,
+        )?
         .collect::<Result<Vec<_>, _>>()?;
     assert_eq!(retrieved, vec![UserKey(42)]);
 
     // After inserting the same user again.
     database.insert(user)?;
     let retrieved = database
-        .iter_by_index(UserNameIndex("A".to_string())..UserNameIndex("Bob".to_string()))?
+        .iter_by_index(
+            UserNameIndex("A".to_string())..UserNameIndex("Bob".to_string()),
+            Direction::Descending,
+        )?
         .collect::<Result<Vec<_>, _>>()?;
     assert_eq!(retrieved, vec![UserKey(42)]);
     Ok(())
@@ -0,0 +1,376 @@
+use std::ops::Range;
+
+use kivis::{
+    ApplyError, BatchOp, BufferOverflowError, Database, DatabaseError, Direction, MIGRATION_BATCH,
+    MemoryStorage, Migrate, Record, Repository, Storage, TableChange, Unifier, UnifierPair,
+    Versioned, manifest,
+};
+use serde::{Deserialize, Serialize};
+
+mod before {
+    use super::*;
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Player {
+        #[key]
+        pub id: u32,
+        #[index]
+        pub nick: String,
+    }
+
+    manifest![Game: Player];
+}
+
+mod after {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct PlayerV1 {
+        pub id: u32,
+        pub nick: String,
+    }
+
+    impl Versioned for PlayerV1 {
+        const VERSION: u32 = 1;
+    }
+
+    impl Migrate for PlayerV1 {
+        type From = Self;
+
+        fn migrate(previous: Self) -> Self {
+            previous
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct PlayerV2 {
+        pub id: u32,
+        pub nick: String,
+        pub level: u16,
+    }
+
+    impl Versioned for PlayerV2 {
+        const VERSION: u32 = 2;
+    }
+
+    impl Migrate for PlayerV2 {
+        type From = PlayerV1;
+
+        fn migrate(previous: PlayerV1) -> Self {
+            Self {
+                id: previous.id,
+                nick: previous.nick,
+                level: 1,
+            }
+        }
+    }
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[record(version = 3)]
+    pub struct Player {
+        #[key]
+        pub id: u32,
+        #[index]
+        pub name: String,
+        #[index(unique)]
+        pub handle: String,
+        pub level: u16,
+    }
+
+    impl Migrate for Player {
+        type From = PlayerV2;
+
+        fn migrate(previous: PlayerV2) -> Self {
+            Self {
+                id: previous.id,
+                handle: previous.nick.to_lowercase(),
+                name: previous.nick,
+                level: previous.level,
+            }
+        }
+    }
+
+    manifest![Game: Player];
+}
+
+mod broken {
+    use super::*;
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[record(version = 2)]
+    pub struct Player {
+        #[key]
+        pub id: u32,
+        pub nick: String,
+    }
+
+    // The chain doesn't reach version 1.
+    impl Migrate for Player {
+        type From = Self;
+
+        fn migrate(previous: Self) -> Self {
+            previous
+        }
+    }
+
+    manifest![Game: Player];
+}
+
+fn stored_players() -> anyhow::Result<MemoryStorage> {
+    let mut store = Database::<_, before::Game>::new(MemoryStorage::default())?;
+    for (id, nick) in [(1, "Ada"), (2, "Bob"), (3, "Cy")] {
+        store.insert(before::Player {
+            id,
+            nick: nick.to_string(),
+        })?;
+    }
+    Ok(store.dissolve())
+}
+
+fn many_players(count: u32) -> anyhow::Result<MemoryStorage> {
+    let mut store = Database::<_, before::Game>::new(MemoryStorage::default())?;
+    for id in 0..count {
+        store.insert(before::Player {
+            id,
+            nick: format!("Player{id}"),
+        })?;
+    }
+    Ok(store.dissolve())
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("storage stopped")]
+struct Stopped;
+
+impl From<BufferOverflowError> for Stopped {
+    fn from(_: BufferOverflowError) -> Self {
+        Stopped
+    }
+}
+
+/// Memory storage that stops applying batches after the first `commits`, as if the process was killed.
+#[derive(Debug)]
+struct Interrupted {
+    data: MemoryStorage,
+    commits: usize,
+}
+
+impl Storage for Interrupted {
+    type Repo = Self;
+    type Unifiers = <MemoryStorage as Storage>::Unifiers;
+
+    fn repository(&self) -> &Self::Repo {
+        self
+    }
+
+    fn repository_mut(&mut self) -> &mut Self::Repo {
+        self
+    }
+}
+
+impl Repository for Interrupted {
+    type K = Vec<u8>;
+    type V = Vec<u8>;
+    type Error = Stopped;
+
+    fn insert_entry(&mut self, key: &[u8], value: &[u8]) -> Result<(), Stopped> {
+        self.data.insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn get_entry(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Stopped> {
+        Ok(self.data.get(key).cloned())
+    }
+
+    fn remove_entry(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, Stopped> {
+        Ok(self.data.remove(key))
+    }
+
+    fn scan_range(
+        &self,
+        range: Range<Vec<u8>>,
+        direction: Direction,
+    ) -> Result<impl Iterator<Item = Result<Vec<u8>, Stopped>>, Stopped> {
+        let keys = direction.apply(self.data.range(range));
+        Ok(keys.map(|(key, _)| Ok(key.clone())))
+    }
+
+    fn apply<U, E>(
+        &mut self,
+        operations: impl Iterator<Item = Result<BatchOp<U>, E>>,
+    ) -> Result<(), ApplyError<E, Stopped>>
+    where
+        U: UnifierPair,
+        U::KeyUnifier: Unifier<D = Vec<u8>>,
+        U::ValueUnifier: Unifier<D = Vec<u8>>,
+    {
+        if self.commits == 0 {
+            return Err(ApplyError::Application(Stopped));
+        }
+        self.commits -= 1;
+        self.data.apply(operations).map_err(|e| match e {
+            ApplyError::Serialization(e) => ApplyError::Serialization(e),
+            ApplyError::Application(_) => ApplyError::Application(Stopped),
+        })
+    }
+}
+
+#[test]
+fn test_migrate_records() -> anyhow::Result<()> {
+    let storage = stored_players()?;
+
+    let mut store = Database::<_, after::Game>::new(storage)?;
+    // The stored records can't be read as the new version before they're migrated.
+    let result = store.get(&after::PlayerKey(2));
+    assert!(
+        matches!(
+            result,
+            Err(DatabaseError::PendingMigration {
+                scope: 0,
+                version: 1
+            })
+        ),
+        "expected a pending migration, got {result:?}"
+    );
+    assert_eq!(store.migrate()?, 3);
+
+    let player = store.get(&after::PlayerKey(2))?;
+    assert_eq!(
+        player,
+        Some(after::Player {
+            id: 2,
+            name: "Bob".to_string(),
+            handle: "bob".to_string(),
+            level: 1,
+        })
+    );
+
+    // The entries of the previous index were dropped and the new indexes rebuilt.
+    let keys = store
+        .iter_by_index::<after::PlayerNameIndex>(.., Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        keys,
+        vec![
+            after::PlayerKey(1),
+            after::PlayerKey(2),
+            after::PlayerKey(3)
+        ]
+    );
+    let cy = store.get_by_unique(&after::PlayerHandleIndex("cy".to_string()))?;
+    assert_eq!(cy.map(|player| player.name), Some("Cy".to_string()));
+
+    // Migrated tables aren't rewritten again.
+    assert_eq!(store.migrate()?, 0);
+    let storage = store.dissolve();
+    let mut store = Database::<_, after::Game>::new(storage)?;
+    assert_eq!(store.migrate()?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_migrate_records_stored_before_schemas() -> anyhow::Result<()> {
+    let mut storage = stored_players()?;
+    // Scope 0, reserved subtable 1, `Schema` entry 1 and `Indexes` entry 4, as written before
+    // fingerprints and index counts were stored.
+    assert!(storage.remove(&vec![0, 1, 0, 0, 0, 1]).is_some());
+    assert!(storage.remove(&vec![0, 1, 0, 0, 0, 4]).is_some());
+
+    // The table holds records, so it stays at version 1 until migrated, and all possible indexes are cleared.
+    let mut store = Database::<_, after::Game>::new(storage)?;
+    assert_eq!(store.migrate()?, 3);
+    assert_eq!(
+        store.get(&after::PlayerKey(1))?.map(|player| player.handle),
+        Some("ada".to_string())
+    );
+    assert_eq!(store.count_by_index::<after::PlayerNameIndex>(..)?, 3);
+    Ok(())
+}
+
+#[test]
+fn test_interrupted_migration_resumes() -> anyhow::Result<()> {
+    let count = 2 * MIGRATION_BATCH as u32 + 5;
+    let storage = Interrupted {
+        data: many_players(count)?,
+        // Three batches delete the entries of the previous index, the fifth commit fails.
+        commits: 4,
+    };
+
+    let mut store = Database::<_, after::Game>::new(storage)?;
+    assert!(matches!(
+        store.migrate(),
+        Err(DatabaseError::Storage(Stopped))
+    ));
+    // Records of the first batch are at the new version and the rest at the old one, so none can be read.
+    assert!(matches!(
+        store.get(&after::PlayerKey(0)),
+        Err(DatabaseError::PendingMigration { .. })
+    ));
+    let mut storage = store.dissolve();
+    storage.commits = usize::MAX;
+
+    let mut store = Database::<_, after::Game>::new(storage)?;
+    assert!(matches!(
+        store.get(&after::PlayerKey(count - 1)),
+        Err(DatabaseError::PendingMigration { .. })
+    ));
+    assert_eq!(store.migrate()?, MIGRATION_BATCH + 5);
+    assert_eq!(
+        store.get(&after::PlayerKey(0))?.map(|player| player.handle),
+        Some("player0".to_string())
+    );
+    assert_eq!(
+        store.count_by_index::<after::PlayerNameIndex>(..)?,
+        count as usize
+    );
+    assert_eq!(
+        store.count_by_index::<after::PlayerHandleIndex>(..)?,
+        count as usize
+    );
+    let last = store.get_by_unique(&after::PlayerHandleIndex(format!("player{}", count - 1)))?;
+    assert_eq!(last.map(|player| player.id), Some(count - 1));
+    assert_eq!(store.migrate()?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_newer_version_is_rejected() -> anyhow::Result<()> {
+    let mut store = Database::<_, after::Game>::new(stored_players()?)?;
+    store.migrate()?;
+
+    let Err(DatabaseError::SchemaMismatch(diff)) =
+        Database::<_, before::Game>::new(store.dissolve())
+    else {
+        anyhow::bail!("expected a schema mismatch");
+    };
+    let Some(TableChange { removed, added, .. }) = diff.tables.first() else {
+        anyhow::bail!("expected a changed table");
+    };
+    assert_eq!(removed.first().map(String::as_str), Some("version 3"));
+    assert_eq!(added.first().map(String::as_str), Some("version 1"));
+    Ok(())
+}
+
+#[test]
+fn test_unsupported_version() -> anyhow::Result<()> {
+    // Counted after opening, which stores the sequence recovered from the records.
+    let entries = Database::<_, broken::Game>::new(stored_players()?)?
+        .dissolve()
+        .len();
+
+    let mut store = Database::<_, broken::Game>::new(stored_players()?)?;
+    let result = store.migrate();
+    assert!(
+        matches!(
+            result,
+            Err(DatabaseError::UnsupportedVersion {
+                scope: 0,
+                version: 1
+            })
+        ),
+        "expected an unsupported version, got {result:?}"
+    );
+    // Nothing is written when the migration fails.
+    assert_eq!(store.dissolve().len(), entries);
+    Ok(())
+}
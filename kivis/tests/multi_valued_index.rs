@@ -0,0 +1,94 @@
+use kivis::{Database, DatabaseError, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Article {
+    #[index(each)]
+    tags: Vec<String>,
+    #[index(each, unique)]
+    slugs: [u16; 2],
+}
+
+manifest![Manifest: Article];
+
+fn article(tags: &[&str], slugs: [u16; 2]) -> Article {
+    Article {
+        tags: tags.iter().map(ToString::to_string).collect(),
+        slugs,
+    }
+}
+
+fn tagged(store: &Database<MemoryStorage, Manifest>, tag: &str) -> anyhow::Result<Vec<ArticleKey>> {
+    Ok(store
+        .iter_by_index_exact(&ArticleTagsIndex(tag.to_string()))?
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+#[test]
+fn test_entry_per_element() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    store.put(article(&["rust", "db"], [1, 2]))?;
+    store.put(article(&["rust"], [3, 4]))?;
+    store.put(article(&[], [5, 6]))?;
+
+    assert_eq!(tagged(&store, "rust")?, vec![ArticleKey(2), ArticleKey(1)]);
+    assert_eq!(tagged(&store, "db")?, vec![ArticleKey(1)]);
+    assert_eq!(store.count_by_index::<ArticleTagsIndex>(..)?, 3);
+
+    let owner = store.get_by_unique(&ArticleSlugsIndex(4))?;
+    assert_eq!(owner, Some(article(&["rust"], [3, 4])));
+    Ok(())
+}
+
+#[test]
+fn test_update_and_remove_every_element() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    let key = store.put(article(&["rust", "db", "kv"], [1, 2]))?;
+
+    store.update(&key, |article: &mut Article| {
+        article.tags = vec!["kv".to_string(), "embedded".to_string()];
+    })?;
+    assert!(tagged(&store, "rust")?.is_empty());
+    assert!(tagged(&store, "db")?.is_empty());
+    assert_eq!(tagged(&store, "kv")?, vec![key.clone()]);
+    assert_eq!(tagged(&store, "embedded")?, vec![key.clone()]);
+
+    store.remove(&key)?;
+    assert_eq!(store.count_by_index::<ArticleTagsIndex>(..)?, 0);
+    assert_eq!(store.count_by_index::<ArticleSlugsIndex>(..)?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_unique_elements() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    store.put(article(&[], [1, 2]))?;
+
+    let result = store.put(article(&[], [3, 2]));
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+    assert_eq!(store.get_by_unique(&ArticleSlugsIndex(3))?, None);
+    Ok(())
+}
+
+#[test]
+fn test_update_large_collection() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    let tags: Vec<String> = (0..2000).map(|i| format!("tag{i}")).collect();
+    let key = store.put(Article {
+        tags: tags.clone(),
+        slugs: [1, 2],
+    })?;
+
+    // Every other element is dropped, and a repeated element is stored once
+    store.update(&key, |article: &mut Article| {
+        article.tags = tags.iter().step_by(2).cloned().collect();
+        article.tags.push("tag0".to_string());
+    })?;
+    assert_eq!(store.count_by_index::<ArticleTagsIndex>(..)?, 1000);
+    assert_eq!(tagged(&store, "tag0")?, vec![key.clone()]);
+    assert!(tagged(&store, "tag1")?.is_empty());
+
+    store.remove(&key)?;
+    assert_eq!(store.count_by_index::<ArticleTagsIndex>(..)?, 0);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use bincode::config::Configuration;
+use kivis::{OrderCheckError, OrderedUnifier, check_order_preserving};
+use proptest::prelude::*;
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Event {
+    Started,
+    Moved(i32, i32),
+    Named { name: String, tags: Vec<u8> },
+}
+
+fn event() -> impl Strategy<Value = Event> {
+    prop_oneof![
+        Just(Event::Started),
+        (any::<i32>(), any::<i32>()).prop_map(|(x, y)| Event::Moved(x, y)),
+        (any::<String>(), any::<Vec<u8>>()).prop_map(|(name, tags)| Event::Named { name, tags }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn ordered_unifier_preserves_integer_order(
+        values in prop::collection::vec((any::<u64>(), any::<i64>(), any::<i8>()), 0..16)
+    ) {
+        prop_assert!(check_order_preserving(&OrderedUnifier::<Vec<u8>>::new(), &values).is_ok());
+    }
+
+    #[test]
+    fn ordered_unifier_preserves_string_order(
+        values in prop::collection::vec((any::<String>(), any::<Vec<u8>>(), any::<u16>()), 0..16)
+    ) {
+        prop_assert!(check_order_preserving(&OrderedUnifier::<Vec<u8>>::new(), &values).is_ok());
+    }
+
+    #[test]
+    fn ordered_unifier_preserves_composite_order(
+        values in prop::collection::vec(
+            (any::<Option<bool>>(), any::<char>(), any::<Vec<Option<i16>>>(), event()),
+            0..16,
+        )
+    ) {
+        prop_assert!(check_order_preserving(&OrderedUnifier::<Vec<u8>>::new(), &values).is_ok());
+    }
+}
+
+#[test]
+fn test_mismatch_is_reported() -> anyhow::Result<()> {
+    // Bincode's varint encoding stores 2 in one byte and 300 in three, starting with 251.
+    let result = check_order_preserving(&Configuration::default(), &[2u64, 300, 251]);
+    let Err(OrderCheckError::Mismatch {
+        left,
+        right,
+        expected,
+        encoded,
+    }) = result
+    else {
+        anyhow::bail!("expected a mismatch, got {result:?}");
+    };
+    assert_eq!((left, right), (1, 2));
+    assert_eq!(
+        (expected, encoded),
+        (std::cmp::Ordering::Greater, std::cmp::Ordering::Less)
+    );
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+
+use kivis::{
+    Database, Direction, MemoryStorage, OrderedError, OrderedUnifier, Record, Unifier, manifest,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+fn encode(value: &impl Serialize) -> anyhow::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    OrderedUnifier::<Vec<u8>>::new()
+        .serialize(&mut buffer, value)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    Ok(buffer)
+}
+
+fn roundtrip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(
+    value: &T,
+) -> anyhow::Result<()> {
+    let encoded = encode(value)?;
+    let decoded: T = OrderedUnifier::<Vec<u8>>::new().deserialize(&encoded)?;
+    assert_eq!(&decoded, value);
+    Ok(())
+}
+
+/// Asserts that the encodings of `values`, given in ascending order, are ascending too.
+fn assert_ordered<T: Serialize + std::fmt::Debug>(values: &[T]) -> anyhow::Result<()> {
+    for pair in values.windows(2) {
+        assert!(
+            encode(&pair[0])? < encode(&pair[1])?,
+            "{:?} should encode below {:?}",
+            pair[0],
+            pair[1]
+        );
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd)]
+enum Shape {
+    Point,
+    Circle(f64),
+    Rect { width: u16, height: u16 },
+}
+
+#[test]
+fn test_integers_are_ordered() -> anyhow::Result<()> {
+    assert_ordered(&[0u64, 2, 255, 256, 300, 70_000, u64::MAX])?;
+    assert_ordered(&[i32::MIN, -300, -1, 0, 1, 300, i32::MAX])?;
+    assert_ordered(&[i128::MIN, -1, 0, i128::MAX])?;
+    assert_eq!(encode(&300u32)?, vec![0, 0, 1, 44]);
+    assert_eq!(encode(&-1i8)?, vec![0x7F]);
+    Ok(())
+}
+
+#[test]
+fn test_floats_are_ordered() -> anyhow::Result<()> {
+    assert_ordered(&[
+        f64::NEG_INFINITY,
+        -1e300,
+        -1.5,
+        -0.0,
+        0.0,
+        f64::MIN_POSITIVE,
+        2.5,
+        f64::INFINITY,
+    ])?;
+    assert_ordered(&[f32::NEG_INFINITY, -2.0f32, 0.0, 0.5, f32::INFINITY])?;
+    roundtrip(&-1.25f64)?;
+    roundtrip(&3.5f32)?;
+    Ok(())
+}
+
+/// Bytes serialized as a byte string rather than a sequence, as `serde_bytes` does.
+#[derive(Debug, PartialEq)]
+struct Blob(Vec<u8>);
+
+impl Serialize for Blob {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Blob {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BlobVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for BlobVisitor {
+            type Value = Blob;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a byte string")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Blob, E> {
+                Ok(Blob(v.to_vec()))
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Blob, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(Blob(bytes))
+            }
+        }
+
+        deserializer.deserialize_bytes(BlobVisitor)
+    }
+}
+
+#[test]
+fn test_strings_and_bytes_are_ordered() -> anyhow::Result<()> {
+    assert_ordered(&["", "\0", "\0\0", "a", "a\0", "a\0b", "aa", "b"])?;
+    assert_eq!(encode(&"a\0")?, vec![b'a', 0x00, 0xFF, 0x00, 0x00]);
+    roundtrip(&"nul\0inside".to_string())?;
+    roundtrip(&b"\0\xff\0".to_vec())?;
+    roundtrip(&Blob(b"\0\xff\0".to_vec()))?;
+    roundtrip(&Blob(b"plain".to_vec()))?;
+    assert_ordered(&[Blob(vec![]), Blob(vec![0]), Blob(vec![0, 1]), Blob(vec![1])])?;
+    Ok(())
+}
+
+#[test]
+fn test_composites_are_ordered() -> anyhow::Result<()> {
+    assert_ordered(&[None, Some(0u8), Some(1)])?;
+    assert_ordered(&[(1u8, "b"), (2, "a"), (2, "b")])?;
+    assert_ordered(&[vec![], vec![1u16], vec![1, 0], vec![2]])?;
+    assert_ordered(&[
+        Shape::Point,
+        Shape::Circle(-1.0),
+        Shape::Circle(2.0),
+        Shape::Rect {
+            width: 1,
+            height: 9,
+        },
+        Shape::Rect {
+            width: 2,
+            height: 0,
+        },
+    ])?;
+    Ok(())
+}
+
+#[test]
+fn test_roundtrip_of_data_model() -> anyhow::Result<()> {
+    roundtrip(&(true, 'λ', -7i16, u128::MAX))?;
+    roundtrip(&Some(vec![Some("x".to_string()), None]))?;
+    roundtrip(&Shape::Rect {
+        width: 3,
+        height: 4,
+    })?;
+    roundtrip(&Shape::Circle(0.5))?;
+    roundtrip(&BTreeMap::from([
+        (1u8, "one".to_string()),
+        (2, "two".to_string()),
+    ]))?;
+    roundtrip(&())?;
+    Ok(())
+}
+
+#[test]
+fn test_invalid_input() -> anyhow::Result<()> {
+    let unifier = OrderedUnifier::<Vec<u8>>::new();
+    assert_eq!(
+        unifier.deserialize::<u32>(&vec![0, 1]),
+        Err(OrderedError::UnexpectedEnd)
+    );
+    assert_eq!(
+        unifier.deserialize::<Option<u8>>(&vec![2]),
+        Err(OrderedError::InvalidTag(2))
+    );
+    assert_eq!(
+        unifier.deserialize::<String>(&vec![b'a', 0, 1]),
+        Err(OrderedError::InvalidEscape(1))
+    );
+    assert_eq!(
+        unifier.deserialize::<char>(&encode(&u32::MAX)?),
+        Err(OrderedError::InvalidChar(u32::MAX))
+    );
+    assert_eq!(
+        unifier.deserialize::<Shape>(&encode(&7u32)?),
+        Err(OrderedError::Custom)
+    );
+    Ok(())
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_heapless_buffer() -> anyhow::Result<()> {
+    let unifier = OrderedUnifier::<heapless::Vec<u8, 8>>::new();
+
+    let mut buffer = heapless::Vec::new();
+    let range = unifier
+        .serialize(&mut buffer, &(1u16, -1i32))
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    assert_eq!(range, (0, 6));
+    assert_eq!(unifier.deserialize::<(u16, i32)>(&buffer)?, (1, -1));
+
+    let overflow = unifier.serialize(&mut buffer, &u64::MAX);
+    assert!(overflow.is_err_and(|e| e.0.is_none()));
+    Ok(())
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Reading {
+    #[key]
+    sensor: u64,
+    #[key]
+    offset: i32,
+    #[index]
+    celsius: i16,
+}
+
+manifest![Manifest: Reading];
+
+#[test]
+fn test_memory_storage_ranges_follow_value_order() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    for (sensor, offset, celsius) in [(2, 0, 5), (300, -5, -20), (300, 7, 300), (2, -300, -1)] {
+        store.insert(Reading {
+            sensor,
+            offset,
+            celsius,
+        })?;
+    }
+
+    let keys = store
+        .iter_keys(
+            ReadingKey(2, i32::MIN)..ReadingKey(300, 0),
+            Direction::Ascending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        keys,
+        vec![ReadingKey(2, -300), ReadingKey(2, 0), ReadingKey(300, -5)]
+    );
+
+    let cold = store
+        .iter_by_index(
+            ReadingCelsiusIndex(i16::MIN)..ReadingCelsiusIndex(0),
+            Direction::Ascending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(cold, vec![ReadingKey(300, -5), ReadingKey(2, -300)]);
+    Ok(())
+}
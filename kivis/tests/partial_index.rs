@@ -0,0 +1,98 @@
+use kivis::{Database, Direction, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+enum Status {
+    #[default]
+    Pending,
+    Done,
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[index(name = "pending_by_owner", fields(owner), when = Self::is_pending)]
+struct Job {
+    owner: u8,
+    #[index(when = Self::is_pending)]
+    status: Status,
+    #[index(sparse, unique)]
+    lock: Option<u32>,
+}
+
+impl Job {
+    fn is_pending(&self) -> bool {
+        self.status == Status::Pending
+    }
+}
+
+manifest![Manifest: Job];
+
+fn job(owner: u8, status: Status, lock: Option<u32>) -> Job {
+    Job {
+        owner,
+        status,
+        lock,
+    }
+}
+
+fn setup() -> anyhow::Result<Database<MemoryStorage, Manifest>> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    store.put(job(1, Status::Pending, None))?;
+    store.put(job(1, Status::Done, Some(7)))?;
+    store.put(job(2, Status::Pending, Some(8)))?;
+    store.put(job(2, Status::Done, None))?;
+    Ok(store)
+}
+
+#[test]
+fn test_predicate_skips_entries() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    assert_eq!(store.count_by_index::<JobStatusIndex>(..)?, 2);
+    let done = store
+        .iter_by_index_exact(&JobStatusIndex(Status::Done))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(done.is_empty());
+
+    let pending = store
+        .iter_by_index::<JobPendingByOwnerIndex>(.., Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(pending, vec![JobKey(1), JobKey(3)]);
+    Ok(())
+}
+
+#[test]
+fn test_sparse_skips_none() -> anyhow::Result<()> {
+    let mut store = setup()?;
+
+    assert_eq!(store.count_by_index::<JobLockIndex>(..)?, 2);
+    let locked = store.get_by_unique(&JobLockIndex(8))?;
+    assert_eq!(locked, Some(job(2, Status::Pending, Some(8))));
+    Ok(())
+}
+
+#[test]
+fn test_update_moves_in_and_out_of_index() -> anyhow::Result<()> {
+    let mut store = setup()?;
+
+    store.update(&JobKey(1), |job: &mut Job| {
+        job.status = Status::Done;
+        job.lock = Some(9);
+    })?;
+    store.update(&JobKey(4), |job: &mut Job| job.status = Status::Pending)?;
+    store.update(&JobKey(3), |job: &mut Job| job.lock = None)?;
+
+    let pending = store
+        .iter_by_index_exact(&JobStatusIndex(Status::Pending))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(pending, vec![JobKey(4), JobKey(3)]);
+    assert_eq!(store.get_by_unique(&JobLockIndex(8))?, None);
+    assert_eq!(
+        store.get_by_unique(&JobLockIndex(9))?,
+        Some(job(1, Status::Done, Some(9)))
+    );
+
+    store.remove(&JobKey(4))?;
+    store.remove(&JobKey(3))?;
+    assert_eq!(store.count_by_index::<JobPendingByOwnerIndex>(..)?, 0);
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::ops::Bound;
+
+use kivis::{Database, Direction, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Reading {
+    #[index]
+    level: u8,
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Sensor {
+    #[index]
+    zone: u8,
+}
+
+manifest![Manifest: Reading, Sensor];
+
+fn setup() -> anyhow::Result<Database<MemoryStorage, Manifest>> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    for level in [10, 20, 30, 40] {
+        store.put(Reading { level })?;
+    }
+    // Records of another type must never leak into the scans.
+    store.put(Sensor { zone: 25 })?;
+    Ok(store)
+}
+
+#[test]
+fn test_iter_keys_range_bounds() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let inclusive = store
+        .iter_keys(ReadingKey(1)..=ReadingKey(2), Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(inclusive, vec![ReadingKey(2), ReadingKey(1)]);
+
+    let open_ended = store
+        .iter_keys(ReadingKey(2).., Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        open_ended,
+        vec![ReadingKey(4), ReadingKey(3), ReadingKey(2)]
+    );
+
+    let excluded_start = store
+        .iter_keys(
+            (
+                Bound::Excluded(ReadingKey(1)),
+                Bound::Excluded(ReadingKey(3)),
+            ),
+            Direction::Descending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(excluded_start, vec![ReadingKey(2)]);
+
+    let full = store
+        .iter_keys::<ReadingKey>(.., Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(full.len(), 4);
+    Ok(())
+}
+
+#[test]
+fn test_iter_by_index_range_bounds() -> anyhow::Result<()> {
+    let store = setup()?;
+
+    let inclusive = store
+        .iter_by_index(
+            ReadingLevelIndex(20)..=ReadingLevelIndex(30),
+            Direction::Descending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(inclusive, vec![ReadingKey(3), ReadingKey(2)]);
+
+    let after = store
+        .iter_by_index(
+            (Bound::Excluded(ReadingLevelIndex(30)), Bound::Unbounded),
+            Direction::Descending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(after, vec![ReadingKey(4)]);
+
+    let up_to = store
+        .iter_by_index(..ReadingLevelIndex(20), Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(up_to, vec![ReadingKey(1)]);
+
+    let full = store
+        .iter_by_index::<ReadingLevelIndex>(.., Direction::Descending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(full.len(), 4);
+    Ok(())
+}
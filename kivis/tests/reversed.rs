@@ -0,0 +1,115 @@
+use kivis::{
+    Database, Direction, Lexicographic, MemoryStorage, OrderedUnifier, Record, Reversed,
+    check_order_preserving, manifest,
+};
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Post {
+    #[key]
+    published_at: Reversed<u64>,
+    title: String,
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[index(name = "by_category_score", fields(category, score))]
+struct Player {
+    category: Lexicographic<String>,
+    score: Reversed<u32>,
+}
+
+manifest![Manifest: Post, Player];
+
+#[test]
+fn test_reversed_keys_scan_newest_first() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    for (published_at, title) in [(300, "middle"), (7, "oldest"), (70_000, "newest")] {
+        store.insert(Post {
+            published_at: Reversed(published_at),
+            title: title.to_string(),
+        })?;
+    }
+
+    let titles = store
+        .iter_all_records::<Post>(Direction::Ascending)?
+        .map(|entry| entry.map(|(_, post)| post.title))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(titles, vec!["newest", "middle", "oldest"]);
+
+    // Ranges follow the reversed order too.
+    let keys = store
+        .iter_keys(
+            PostKey(Reversed(1_000))..PostKey(Reversed(0)),
+            Direction::Ascending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(keys, vec![PostKey(Reversed(300)), PostKey(Reversed(7))]);
+
+    let post = store.get(&PostKey(Reversed(300)))?;
+    assert_eq!(post.map(|post| post.title), Some("middle".to_string()));
+    Ok(())
+}
+
+#[test]
+fn test_mixed_direction_composite_index() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    let mut keys = Vec::new();
+    for (category, score) in [
+        ("chess", 1200),
+        ("go", 900),
+        ("chess", 2100),
+        ("chess", 300),
+    ] {
+        keys.push(store.put(Player {
+            category: category.into(),
+            score: Reversed(score),
+        })?);
+    }
+
+    let chess = store
+        .iter_by_index_with_prefix::<PlayerByCategoryScoreIndex, _>(
+            &(Lexicographic::<String>::from("chess"),),
+            Direction::Ascending,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        chess,
+        vec![keys[2].clone(), keys[0].clone(), keys[3].clone()]
+    );
+
+    // Categories ascend while scores descend within each of them.
+    let all = store
+        .iter_by_index::<PlayerByCategoryScoreIndex>(.., Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        all,
+        vec![
+            keys[2].clone(),
+            keys[0].clone(),
+            keys[3].clone(),
+            keys[1].clone()
+        ]
+    );
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn reversed_inverts_order(
+        values in prop::collection::vec(any::<(i32, String, Option<u8>)>(), 0..16)
+    ) {
+        let reversed = values.into_iter().map(Reversed).collect::<Vec<_>>();
+        prop_assert!(check_order_preserving(&OrderedUnifier::<Vec<u8>>::new(), &reversed).is_ok());
+        prop_assert!(check_order_preserving(&bincode::config::standard(), &reversed).is_ok());
+    }
+
+    #[test]
+    fn reversed_roundtrips(value in any::<(i64, String, Vec<u8>)>()) {
+        let value = Reversed(value);
+        let encoded = postcard::to_allocvec(&value).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        let decoded: Reversed<(i64, String, Vec<u8>)> =
+            postcard::from_bytes(&encoded).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(decoded, value);
+    }
+}
@@ -0,0 +1,259 @@
+use kivis::{
+    Database, DatabaseEntry, DatabaseError, DeriveKey, FieldDescriptor, IndexDescriptor,
+    KeyDescriptor, MemoryStorage, Record, RecordDescriptor, RecordKey, RecordSchema, TableChange,
+    manifest,
+};
+use serde::{Deserialize, Serialize};
+
+mod before {
+    use super::*;
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Account {
+        #[key]
+        pub id: u32,
+        #[index]
+        pub email: String,
+        pub tags: Vec<(u8, String)>,
+    }
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[autoincrement(u16)]
+    pub struct Login(pub u32, pub Option<u64>);
+
+    manifest![App: Account = 1, Login = 2];
+}
+
+mod after {
+    use super::*;
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Account {
+        #[key]
+        pub id: u32,
+        #[index(unique)]
+        pub email: String,
+        pub tags: Vec<(u8, String)>,
+        pub verified: bool,
+    }
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[autoincrement(u16)]
+    pub struct Login(pub u32, pub Option<u64>);
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Audit {
+        pub message: String,
+    }
+
+    manifest![App: Account = 1, Login = 2, Audit = 3];
+}
+
+mod renamed {
+    use super::*;
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Account {
+        #[key]
+        pub account_id: u32,
+        #[index]
+        pub address: std::string::String,
+        pub labels: std::vec::Vec<(u8, String)>,
+    }
+
+    manifest![App: Account = 1];
+}
+
+mod added {
+    use super::*;
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Account {
+        #[key]
+        pub id: u32,
+        #[index]
+        pub email: String,
+        pub tags: Vec<(u8, String)>,
+    }
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Audit {
+        pub message: String,
+    }
+
+    manifest![App: Account = 1, Audit = 3; retired = [2]];
+}
+
+mod manual {
+    use super::*;
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+    pub struct MemoKey(pub u32);
+
+    impl RecordKey for MemoKey {
+        type Record = Memo;
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Memo {
+        pub id: u32,
+        pub text: String,
+    }
+
+    impl DatabaseEntry for Memo {
+        type Key = MemoKey;
+    }
+
+    impl DeriveKey for Memo {
+        type Key = MemoKey;
+
+        fn key(memo: &Memo) -> MemoKey {
+            MemoKey(memo.id)
+        }
+    }
+
+    impl RecordSchema for Memo {}
+
+    #[derive(Record, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Audit {
+        pub message: String,
+    }
+
+    manifest![Notebook: Audit, Memo];
+}
+
+#[test]
+fn test_fingerprint() {
+    assert_eq!(
+        before::Account::FINGERPRINT,
+        "fields(u32, String, Vec<(u8, String)>); key: fields(u32); index 0: String"
+    );
+    assert_eq!(
+        before::Login::FINGERPRINT,
+        "fields(u32, Option<u64>); key: autoincrement(u16)"
+    );
+    // Field names and type paths don't change the encoding
+    assert_eq!(renamed::Account::FINGERPRINT, before::Account::FINGERPRINT);
+}
+
+const fn field(name: &'static str, type_name: &'static str) -> FieldDescriptor {
+    FieldDescriptor { name, type_name }
+}
+
+#[test]
+fn test_record_schema() {
+    const ACCOUNT: RecordDescriptor = RecordDescriptor {
+        name: "Account",
+        scope: 1,
+        fields: &[
+            field("id", "u32"),
+            field("email", "String"),
+            field("tags", "Vec<(u8, String)>"),
+        ],
+        key: KeyDescriptor::Fields(&[field("id", "u32")]),
+        indexes: &[IndexDescriptor {
+            name: "Email",
+            discriminator: 0,
+            fields: &[field("email", "String")],
+            unique: false,
+        }],
+    };
+    assert_eq!(before::Account::SCHEMA, ACCOUNT);
+    assert_eq!(
+        before::Login::SCHEMA.fields,
+        &[field("0", "u32"), field("1", "Option<u64>")]
+    );
+    assert_eq!(
+        before::Login::SCHEMA.key,
+        KeyDescriptor::Autoincrement { type_name: "u16" }
+    );
+}
+
+#[test]
+fn test_catalog() {
+    let tables = after::App::catalog()
+        .iter()
+        .map(|table| (table.scope, table.name))
+        .collect::<Vec<_>>();
+    assert_eq!(tables, vec![(1, "Account"), (2, "Login"), (3, "Audit")]);
+    assert!(after::App::catalog()[0].indexes[0].unique);
+}
+
+#[test]
+fn test_schema_mismatch() -> anyhow::Result<()> {
+    let mut store = Database::<_, before::App>::new(MemoryStorage::default())?;
+    store.put(before::Login(7, None))?;
+    let storage = store.dissolve();
+    let entries = storage.len();
+
+    let Err(DatabaseError::SchemaMismatch(diff)) = Database::<_, after::App>::new(storage.clone())
+    else {
+        anyhow::bail!("expected a schema mismatch");
+    };
+    assert_eq!(
+        diff.tables,
+        vec![TableChange {
+            scope: 1,
+            removed: vec![
+                "fields(u32, String, Vec<(u8, String)>)".to_string(),
+                "index 0: String".to_string(),
+            ],
+            added: vec![
+                "fields(u32, String, Vec<(u8, String)>, bool)".to_string(),
+                "index 0: String unique".to_string(),
+            ],
+        }]
+    );
+    assert_eq!(
+        diff.to_string(),
+        "scope 1:\n\
+         \x20 - fields(u32, String, Vec<(u8, String)>)\n\
+         \x20 - index 0: String\n\
+         \x20 + fields(u32, String, Vec<(u8, String)>, bool)\n\
+         \x20 + index 0: String unique\n"
+    );
+    // The schema of the new table isn't stored when opening fails.
+    assert_eq!(storage.len(), entries);
+    Ok(())
+}
+
+#[test]
+fn test_added_table_is_stored() -> anyhow::Result<()> {
+    let store = Database::<_, before::App>::new(MemoryStorage::default())?;
+    let storage = store.dissolve();
+    let entries = storage.len();
+
+    // The schema and index count of the new table.
+    let store = Database::<_, added::App>::new(storage)?;
+    let storage = store.dissolve();
+    assert_eq!(storage.len(), entries + 2);
+
+    // Reopening with the same manifest checks the stored schemas without writing.
+    let store = Database::<_, added::App>::new(storage)?;
+    assert_eq!(store.dissolve().len(), entries + 2);
+    Ok(())
+}
+
+#[test]
+fn test_catalog_of_manual_records() -> anyhow::Result<()> {
+    assert_eq!(
+        manual::Notebook::catalog()[1],
+        RecordDescriptor {
+            name: "",
+            scope: 1,
+            fields: &[],
+            key: KeyDescriptor::Opaque,
+            indexes: &[],
+        }
+    );
+    assert_eq!(manual::Notebook::catalog()[0].name, "Audit");
+
+    let mut store = Database::<_, manual::Notebook>::new(MemoryStorage::default())?;
+    let memo = manual::Memo {
+        id: 7,
+        text: "by hand".to_string(),
+    };
+    store.insert(memo.clone())?;
+    assert_eq!(store.get(&manual::MemoKey(7))?, Some(memo));
+    Ok(())
+}
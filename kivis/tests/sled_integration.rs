@@ -1,7 +1,7 @@
 #[cfg(feature = "sled")]
 mod tests {
 
-    use kivis::{Database, Record, manifest};
+    use kivis::{Database, Direction, OrderedSled, Record, manifest};
 
     use serde::{Deserialize, Serialize};
 
@@ -57,10 +57,62 @@ mod tests {
     }
 
     #[test]
-    fn test_sled_iteration() -> Result<(), Box<dyn std::error::Error>> {
+    fn test_sled_sequence_persistence() -> Result<(), Box<dyn std::error::Error>> {
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let path = temp_dir.path().join("test.db");
+
+        let removed = {
+            let mut store = Database::<_, TestManifest>::new(sled::open(&path)?)?;
+            store.put(TestRecord::default())?;
+            let key = store.put(TestRecord::default())?;
+            store.remove(&key)?;
+            store.dissolve().flush()?;
+            key
+        };
+
         let mut store = Database::<_, TestManifest>::new(sled::open(&path)?)?;
+        assert_eq!(store.sequence::<TestRecordKey>()?, Some(removed.clone()));
+        let key = store.put(TestRecord::default())?;
+        assert!(key > removed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sled_sequence_recovered_from_records() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("test.db");
+
+        // Postcard writes keys as varints, so 255 sorts after 256 and the greatest key isn't the last one scanned
+        let mut store = Database::<_, TestManifest>::new(sled::open(&path)?)?;
+        let mut last = store.put(TestRecord::default())?;
+        for i in 0..300u32 {
+            last = store.put(TestRecord {
+                data: i.to_be_bytes().to_vec(),
+            })?;
+        }
+        let storage = store.dissolve();
+        // Drops the sequence entry, as in databases written before sequences were persisted:
+        // scope 0, reserved subtable 1, `Sequence` entry 0.
+        assert!(storage.remove([0u8, 1, 0])?.is_some());
+
+        let mut store = Database::<_, TestManifest>::new(storage)?;
+        assert_eq!(store.sequence::<TestRecordKey>()?, Some(last.clone()));
+        let key = store.put(TestRecord::default())?;
+        assert!(key > last);
+        assert_eq!(
+            store.get(&last)?,
+            Some(TestRecord {
+                data: 299u32.to_be_bytes().to_vec()
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sled_iteration() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("test.db");
+        let mut store = Database::<_, TestManifest>::new(OrderedSled(sled::open(&path)?))?;
 
         let record1 = TestRecord { data: vec![1, 2] };
         let record2 = TestRecord { data: vec![3, 4] };
@@ -69,13 +121,54 @@ mod tests {
         let key2 = store.put(record2)?;
 
         let keys: Vec<_> = store
-            .iter_keys(TestRecordKey(0)..TestRecordKey(100))?
+            .iter_keys(TestRecordKey(0)..TestRecordKey(100), Direction::Descending)?
             .collect::<Result<Vec<_>, _>>()?;
 
         // Keys should be in reverse order
-        assert_eq!(keys.len(), 2);
-        assert!(keys.contains(&key1));
-        assert!(keys.contains(&key2));
+        assert_eq!(keys, vec![key2.clone(), key1.clone()]);
+
+        let keys: Vec<_> = store
+            .iter_keys(TestRecordKey(0)..TestRecordKey(100), Direction::Ascending)?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(keys, vec![key1, key2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sled_iter_records() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("test.db");
+        let mut store = Database::<_, TestManifest>::new(OrderedSled(sled::open(&path)?))?;
+
+        let record1 = TestRecord { data: vec![1, 2] };
+        let record2 = TestRecord { data: vec![3, 4] };
+
+        let key1 = store.put(record1.clone())?;
+        let key2 = store.put(record2.clone())?;
+
+        let records: Vec<_> = store
+            .iter_all_records::<TestRecord>(Direction::Descending)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(records, vec![(key2, record2), (key1, record1)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sled_contains_and_count() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp_dir.path().join("test.db");
+        let mut store = Database::<_, TestManifest>::new(OrderedSled(sled::open(&path)?))?;
+
+        let key1 = store.put(TestRecord { data: vec![1, 2] })?;
+        let key2 = store.put(TestRecord { data: vec![3, 4] })?;
+        store.remove(&key1)?;
+
+        assert!(!store.contains(&key1)?);
+        assert!(store.contains(&key2)?);
+        assert_eq!(store.count::<TestRecord>(..)?, 1);
 
         Ok(())
     }
@@ -3,5 +3,8 @@ fn ui() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/conflicting_key_strategies.rs");
     t.compile_fail("tests/ui/invalid_manifest_definition.rs");
+    t.compile_fail("tests/ui/range_requires_order_preserving.rs");
+    t.compile_fail("tests/ui/duplicate_scope.rs");
+    t.compile_fail("tests/ui/retired_scope.rs");
     t.pass("tests/ui/no_std.rs");
 }
@@ -0,0 +1,17 @@
+use kivis::{Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Debug, Serialize, Deserialize)]
+struct User {
+    name: String,
+}
+
+#[derive(Record, Debug, Serialize, Deserialize)]
+#[record(scope = 1)]
+struct Post {
+    title: String,
+}
+
+manifest![App: User = 1, Post];
+
+fn main() {}
@@ -0,0 +1,36 @@
+use bincode::config::Configuration;
+use kivis::{Database, Direction, MemoryStorage, Record, Storage, manifest};
+
+#[derive(Record, Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct User {
+    #[index]
+    age: u64,
+}
+
+manifest![Manifest: User];
+
+/// Storage whose keys use bincode's varint encoding, which doesn't preserve order.
+#[derive(Default)]
+struct VarintStorage(MemoryStorage);
+
+impl Storage for VarintStorage {
+    type Repo = MemoryStorage;
+    type Unifiers = (Configuration, Configuration);
+    fn repository(&self) -> &Self::Repo {
+        &self.0
+    }
+
+    fn repository_mut(&mut self) -> &mut Self::Repo {
+        &mut self.0
+    }
+}
+
+fn main() {
+    let db = Database::<_, Manifest>::new(VarintStorage::default()).unwrap();
+
+    // Point lookups work with any key unifier.
+    let _ = db.iter_by_index_exact(&UserAgeIndex(30));
+
+    // This should fail - range scans need an order-preserving key unifier
+    let _ = db.iter_by_index(UserAgeIndex(2)..UserAgeIndex(300), Direction::Ascending);
+}
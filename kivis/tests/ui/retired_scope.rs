@@ -0,0 +1,17 @@
+use kivis::{Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Debug, Serialize, Deserialize)]
+struct User {
+    name: String,
+}
+
+#[derive(Record, Debug, Serialize, Deserialize)]
+struct Post {
+    title: String,
+}
+
+// `Post` takes its position, which was retired.
+manifest![App: User = 0, Post; retired = [1]];
+
+fn main() {}
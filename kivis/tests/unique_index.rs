@@ -0,0 +1,139 @@
+use kivis::{Database, DatabaseError, Direction, MemoryStorage, Record, manifest};
+use serde::{Deserialize, Serialize};
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Account {
+    #[key]
+    id: u32,
+    #[index(unique)]
+    email: String,
+    #[index]
+    team: String,
+}
+
+#[derive(Record, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Device {
+    #[index(unique)]
+    serial: u64,
+}
+
+manifest![Manifest: Account, Device];
+
+fn account(id: u32, email: &str) -> Account {
+    Account {
+        id,
+        email: email.to_string(),
+        team: "core".to_string(),
+    }
+}
+
+#[test]
+fn test_get_by_unique() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    store.insert(account(1, "alice@example.com"))?;
+    store.insert(account(2, "bob@example.com"))?;
+
+    let bob = store.get_by_unique(&AccountEmailIndex("bob@example.com".to_string()))?;
+    assert_eq!(bob, Some(account(2, "bob@example.com")));
+    let missing = store.get_by_unique(&AccountEmailIndex("eve@example.com".to_string()))?;
+    assert_eq!(missing, None);
+
+    // Unique indexes can still be scanned like any other index.
+    let mut keys = store
+        .iter_by_index::<AccountEmailIndex>(.., Direction::Ascending)?
+        .collect::<Result<Vec<_>, _>>()?;
+    keys.sort();
+    assert_eq!(keys, vec![AccountKey(1), AccountKey(2)]);
+    assert_eq!(store.count_by_index::<AccountEmailIndex>(..)?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_insert_violating_unique_index() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    store.insert(account(1, "alice@example.com"))?;
+
+    let result = store.insert(account(2, "alice@example.com"));
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+    assert!(!store.contains(&AccountKey(2))?);
+
+    // Rewriting the owner with the same value is allowed.
+    let mut renamed = account(1, "alice@example.com");
+    renamed.team = "docs".to_string();
+    store.insert(renamed.clone())?;
+    assert_eq!(
+        store.get_by_unique(&AccountEmailIndex("alice@example.com".to_string()))?,
+        Some(renamed)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_released_value_can_be_taken() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    store.insert(account(1, "alice@example.com"))?;
+    store.insert(account(2, "bob@example.com"))?;
+
+    store.update(&AccountKey(1), |account: &mut Account| {
+        account.email = "alice@work.example.com".to_string();
+    })?;
+    store.insert(account(3, "alice@example.com"))?;
+
+    store.remove(&AccountKey(2))?;
+    store.insert(account(4, "bob@example.com"))?;
+
+    let owners = [
+        "alice@work.example.com",
+        "alice@example.com",
+        "bob@example.com",
+    ]
+    .into_iter()
+    .map(|email| {
+        store
+            .get_by_unique(&AccountEmailIndex(email.to_string()))
+            .map(|account| account.map(|account| account.id))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(owners, vec![Some(1), Some(3), Some(4)]);
+    Ok(())
+}
+
+#[test]
+fn test_put_violating_unique_index() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+    let first = store.put(Device { serial: 7 })?;
+
+    let result = store.put(Device { serial: 7 });
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+    assert_eq!(store.count::<Device>(..)?, 1);
+    assert_eq!(
+        store.get_by_unique(&DeviceSerialIndex(7))?,
+        Some(Device { serial: 7 })
+    );
+    assert!(store.contains(&first)?);
+    Ok(())
+}
+
+#[test]
+fn test_transaction_violating_unique_index() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+
+    let mut transaction = store.create_transaction();
+    transaction.insert(account(1, "alice@example.com"))?;
+    transaction.insert(account(2, "alice@example.com"))?;
+    let result = store.commit(transaction);
+    assert!(matches!(result, Err(DatabaseError::UniqueViolation)));
+    assert_eq!(store.count::<Account>(..)?, 0);
+
+    // A value released earlier in the same transaction can be taken.
+    store.insert(account(1, "alice@example.com"))?;
+    let mut transaction = store.create_transaction();
+    transaction.remove(&AccountKey(1), &account(1, "alice@example.com"))?;
+    transaction.insert(account(2, "alice@example.com"))?;
+    store.commit(transaction)?;
+    assert_eq!(
+        store.get_by_unique(&AccountEmailIndex("alice@example.com".to_string()))?,
+        Some(account(2, "alice@example.com"))
+    );
+    Ok(())
+}
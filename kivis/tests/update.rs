@@ -0,0 +1,78 @@
+use anyhow::Context;
+use kivis::{Database, DatabaseError, Lexicographic, MemoryStorage, Record, manifest};
+
+// Define a record type for a Task, with an autoincremented key.
+#[derive(Record, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct Task {
+    #[index]
+    status: Lexicographic<String>,
+    title: String,
+}
+
+// Define a record type for an Account, keyed by its handle.
+#[derive(Record, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct Account {
+    #[key]
+    handle: String,
+    balance: u64,
+}
+
+manifest![Manifest: Task, Account];
+
+#[test]
+fn test_update_autoincrement_record() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+
+    let key = store.put(Task {
+        status: "todo".into(),
+        title: "Write docs".to_string(),
+    })?;
+
+    let existed = store.update::<Task>(&key, |task| task.status = "done".into())?;
+    assert!(existed);
+
+    let task = store.get(&key)?.context("Missing")?;
+    assert_eq!(task.status, "done");
+    assert_eq!(task.title, "Write docs");
+
+    // Index entries follow the updated field.
+    let todo = store
+        .iter_by_index_exact(&TaskStatusIndex("todo".into()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(todo.is_empty());
+    let done = store
+        .iter_by_index_exact(&TaskStatusIndex("done".into()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(done, vec![key]);
+    Ok(())
+}
+
+#[test]
+fn test_update_missing_record() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+
+    let existed = store.update::<Task>(&TaskKey(7), |_| panic!("Closure must not be called"))?;
+    assert!(!existed);
+    assert_eq!(store.get(&TaskKey(7))?, None);
+    Ok(())
+}
+
+#[test]
+fn test_update_derived_key_record() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+
+    let key = store.insert(Account {
+        handle: "alice".to_string(),
+        balance: 10,
+    })?;
+
+    assert!(store.update::<Account>(&key, |account| account.balance += 5)?);
+    assert_eq!(store.get(&key)?.context("Missing")?.balance, 15);
+
+    // Changing the derived key is rejected and leaves the record untouched.
+    let result = store.update::<Account>(&key, |account| account.handle = "bob".to_string());
+    assert!(matches!(result, Err(DatabaseError::DerivedKeyChanged)));
+    assert_eq!(store.get(&key)?.context("Missing")?.balance, 15);
+    assert_eq!(store.get(&AccountKey("bob".to_string()))?, None);
+    Ok(())
+}
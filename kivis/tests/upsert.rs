@@ -0,0 +1,68 @@
+use anyhow::Context;
+use kivis::{Database, Lexicographic, MemoryStorage, Record, manifest};
+
+// Define a record type for a User, keyed by its id.
+#[derive(Record, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct User {
+    #[key]
+    id: u64,
+    #[index]
+    name: Lexicographic<String>,
+    #[index]
+    email: Lexicographic<String>,
+}
+
+manifest![Manifest: User];
+
+fn user(name: &str, email: &str) -> User {
+    User {
+        id: 42,
+        name: name.into(),
+        email: email.into(),
+    }
+}
+
+#[test]
+fn test_reinsert_replaces_index_entries() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+
+    let key = store.insert(user("Alice", "alice@example.com"))?;
+    let updated = user("Alicia", "alice@example.com");
+    assert_eq!(store.insert(updated.clone())?, key);
+
+    // The old index value no longer points at the record.
+    let stale = store
+        .iter_by_index_exact(&UserNameIndex("Alice".into()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(stale.is_empty());
+
+    let renamed = store
+        .iter_by_index_exact(&UserNameIndex("Alicia".into()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(renamed, vec![key.clone()]);
+
+    // The unchanged index is left as it was.
+    let by_email = store
+        .iter_by_index_exact(&UserEmailIndex("alice@example.com".into()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(by_email, vec![key.clone()]);
+
+    assert_eq!(store.get(&key)?.context("Missing")?, updated);
+    Ok(())
+}
+
+#[test]
+fn test_reinsert_unchanged_record() -> anyhow::Result<()> {
+    let mut store = Database::<_, Manifest>::new(MemoryStorage::default())?;
+
+    let original = user("Bob", "bob@example.com");
+    let key = store.insert(original.clone())?;
+    store.insert(original.clone())?;
+
+    let by_name = store
+        .iter_by_index_exact(&UserNameIndex("Bob".into()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(by_name, vec![key.clone()]);
+    assert_eq!(store.get(&key)?.context("Missing")?, original);
+    Ok(())
+}